@@ -0,0 +1,223 @@
+//! CSS scoping shared by the runtime (`azumi::scope_css`) and the macros:
+//! class names get a per-component suffix, CSS Modules style.
+//!
+//! Selectors are rewritten on lightningcss's parsed stylesheet, so rules inside
+//! `@media`, `@supports`, `@container` and `@layer`, nested rules, `:is()` /
+//! `:where()` arguments and pseudo-elements are all handled the same way.
+
+use lightningcss::selector::{Component, Selector};
+use lightningcss::stylesheet::{ParserFlags, ParserOptions, PrinterOptions, StyleSheet};
+use lightningcss::values::ident::Ident;
+use lightningcss::visit_types;
+use lightningcss::visitor::{Visit, VisitTypes, Visitor};
+use std::collections::HashSet;
+use std::convert::Infallible;
+
+/// Append `-{suffix}` to every class name (CSS Modules style).
+/// `.card:is(.active, .open)` -> `.card-s1:is(.active-s1, .open-s1)`
+pub fn rename_classes(css: &str, suffix: &str) -> String {
+    let Some(mut sheet) = parse(css) else {
+        return css.to_string();
+    };
+    if sheet.visit(&mut Renamer { suffix }).is_err() {
+        return css.to_string();
+    }
+    match sheet.to_css(PrinterOptions::default()) {
+        Ok(out) => out.code,
+        Err(_) => css.to_string(),
+    }
+}
+
+/// Class names and ids used by the stylesheet's selectors, including those
+/// inside at-rules, nested rules and `:is()` / `:not()` arguments
+pub fn selector_names(css: &str) -> (HashSet<String>, HashSet<String>) {
+    let mut collector = Collector {
+        classes: HashSet::new(),
+        ids: HashSet::new(),
+    };
+    if let Some(mut sheet) = parse(css) {
+        let _ = sheet.visit(&mut collector);
+    }
+    (collector.classes, collector.ids)
+}
+
+/// Parser options for azumi's CSS: custom media queries are enabled, and rules
+/// lightningcss can't parse are dropped, as a browser would
+pub fn parser_options<'o, 'i>() -> ParserOptions<'o, 'i> {
+    ParserOptions {
+        flags: ParserFlags::CUSTOM_MEDIA,
+        error_recovery: true,
+        ..ParserOptions::default()
+    }
+}
+
+fn parse(css: &str) -> Option<StyleSheet<'_, '_>> {
+    StyleSheet::parse(css, parser_options()).ok()
+}
+
+struct Renamer<'a> {
+    suffix: &'a str,
+}
+
+impl<'i> Visitor<'i> for Renamer<'_> {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(SELECTORS)
+    }
+
+    // Called for the selectors of every style rule, at any depth of at-rules and nesting
+    fn visit_selector(&mut self, selector: &mut Selector<'i>) -> Result<(), Self::Error> {
+        rename_selector(selector, self.suffix);
+        Ok(())
+    }
+}
+
+fn rename_selector(selector: &mut Selector, suffix: &str) {
+    for component in selector.iter_mut_raw_match_order() {
+        match component {
+            Component::Class(name) => {
+                *name = Ident(format!("{}-{}", name.0, suffix).into());
+            }
+            Component::Negation(list)
+            | Component::Is(list)
+            | Component::Where(list)
+            | Component::Has(list)
+            | Component::Any(_, list) => {
+                for inner in list.iter_mut() {
+                    rename_selector(inner, suffix);
+                }
+            }
+            Component::Slotted(inner) | Component::Host(Some(inner)) => {
+                rename_selector(inner, suffix)
+            }
+            _ => {}
+        }
+    }
+}
+
+struct Collector {
+    classes: HashSet<String>,
+    ids: HashSet<String>,
+}
+
+impl<'i> Visitor<'i> for Collector {
+    type Error = Infallible;
+
+    fn visit_types(&self) -> VisitTypes {
+        visit_types!(SELECTORS)
+    }
+
+    fn visit_selector(&mut self, selector: &mut Selector<'i>) -> Result<(), Self::Error> {
+        self.collect(selector);
+        Ok(())
+    }
+}
+
+impl Collector {
+    fn collect(&mut self, selector: &Selector) {
+        for component in selector.iter_raw_match_order() {
+            match component {
+                Component::Class(name) => {
+                    self.classes.insert(name.0.to_string());
+                }
+                Component::ID(name) => {
+                    self.ids.insert(name.0.to_string());
+                }
+                Component::Negation(list)
+                | Component::Is(list)
+                | Component::Where(list)
+                | Component::Has(list)
+                | Component::Any(_, list) => list.iter().for_each(|inner| self.collect(inner)),
+                Component::Slotted(inner) | Component::Host(Some(inner)) => self.collect(inner),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compact(css: &str) -> String {
+        css.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn test_rename_basic_selectors() {
+        let out = rename_classes(".button, a.link > span { color: red; }", "s1");
+        assert!(out.contains(".button-s1, a.link-s1 > span"));
+        assert!(out.contains("color: red;"));
+    }
+
+    #[test]
+    fn test_rename_pseudo_elements_and_classes() {
+        let out = rename_classes(
+            ".tip::before { content: \"!\"; } .btn:hover { color: red; }",
+            "s1",
+        );
+        assert!(out.contains(".tip-s1:before"));
+        assert!(out.contains(".btn-s1:hover"));
+    }
+
+    #[test]
+    fn test_rename_inside_at_rules() {
+        let css = "@media (width >= 600px) { .card { padding: 1rem; } }
+            @supports (display: grid) { .grid { display: grid; } }
+            @container (min-width: 400px) { .cell { gap: 1rem; } }
+            @layer base { .title { margin: 0; } }";
+        let out = compact(&rename_classes(css, "s1"));
+        for selector in [".card", ".grid", ".cell", ".title"] {
+            assert!(
+                out.contains(&format!("{}-s1 {{", selector)),
+                "{} not renamed in {}",
+                selector,
+                out
+            );
+        }
+        assert!(out.contains("@media"));
+        assert!(out.contains("@layer base"));
+    }
+
+    #[test]
+    fn test_rename_nesting() {
+        let out = compact(&rename_classes(
+            ".card { &:hover { color: blue; } .title { margin: 0; } }",
+            "s1",
+        ));
+        assert!(out.contains(".card-s1 {"));
+        assert!(out.contains("&:hover {"));
+        assert!(out.contains(".title-s1 {"));
+    }
+
+    #[test]
+    fn test_rename_classes() {
+        let out = compact(&rename_classes(
+            "div.card > .item:not(.done)::after { color: red; } @media print { :where(.card) { display: none; } }",
+            "s1",
+        ));
+        assert!(out.contains("div.card-s1 > .item-s1:not(.done-s1):after"));
+        assert!(out.contains(":where(.card-s1)"));
+    }
+
+    #[test]
+    fn test_selector_names() {
+        let (classes, ids) = selector_names(
+            ".card { color: red; .title { margin: 0; } }
+            @media (--narrow) { #main:not(.wide) { padding: 0; } }
+            @custom-media --narrow (max-width: 600px);",
+        );
+        let mut classes: Vec<_> = classes.into_iter().collect();
+        classes.sort();
+        assert_eq!(classes, ["card", "title", "wide"]);
+        assert!(ids.contains("main"));
+    }
+
+    #[test]
+    fn test_invalid_rules_are_dropped() {
+        let out = rename_classes(".a { color: red; } .b > { color: blue; }", "s1");
+        assert!(out.contains(".a-s1"));
+        assert!(!out.contains(".b"));
+    }
+}
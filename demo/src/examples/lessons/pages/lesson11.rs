@@ -76,14 +76,14 @@ pub fn user_loader_view<'a>(state: &'a UserLoader) -> impl Component + 'a {
             .error_state {
                 background: "#fef2f2"; color: "#991b1b";
                 padding: "1rem"; border-radius: "8px";
-                display: "flex"; align_items: "center"; gap: "0.5rem";
+                display: "flex"; align-items: "center"; gap: "0.5rem";
                 margin-bottom: "1rem";
             }
 
             /* Data State */
             .user_list { list-style: "none"; padding: "0"; }
             .user_item {
-                display: "flex"; align_items: "center"; gap: "1rem";
+                display: "flex"; align-items: "center"; gap: "1rem";
                 padding: "1rem"; border-bottom: "1px solid #f3f4f6";
             }
             .avatar {
@@ -0,0 +1,4 @@
+# French catalog
+greeting = Bonjour, { $name } !
+items = { $count } articles
+nav.home = Accueil
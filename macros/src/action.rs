@@ -2,9 +2,16 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, FnArg, ItemFn, PatType};
 
-pub fn expand_action(item: TokenStream) -> TokenStream {
+pub fn expand_action(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input_fn = parse_macro_input!(item as ItemFn);
 
+    // Parse attributes to find guard = path (may be repeated)
+    let args = parse_macro_input!(attr with syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated);
+    let guards = match parse_guards(args) {
+        Ok(guards) => guards,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     let fn_name = &input_fn.sig.ident;
     let fn_vis = &input_fn.vis;
     let fn_async = &input_fn.sig.asyncness;
@@ -77,6 +84,7 @@ pub fn expand_action(item: TokenStream) -> TokenStream {
     // Helper to return MethodRouter
     // We need a unique name for this too
     let router_helper_name = quote::format_ident!("{}_router", fn_name);
+    let guard_layers = guard_layers(&guards);
 
     let expanded = quote! {
         // Original function (modified to be called by wrapper if needed, or just keep it)
@@ -95,6 +103,7 @@ pub fn expand_action(item: TokenStream) -> TokenStream {
         // Generated Axum handler
         // We use a different name so we don't conflict
         pub async fn #wrapper_name(
+            _csrf: azumi::csrf::CsrfGuard,
             #(#extractor_args,)*
             axum::extract::Json(payload): axum::extract::Json<#payload_type>
         ) -> impl axum::response::IntoResponse {
@@ -109,13 +118,14 @@ pub fn expand_action(item: TokenStream) -> TokenStream {
             // So we can just pass `payload` as the last argument.
 
             let result = #fn_name(#(#call_args,)* payload).await;
-            azumi::render_to_string(&result)
+            azumi::action::IntoActionResponse::into_action_response(result)
         }
 
         // Helper to return MethodRouter
         #[allow(non_snake_case)]
         pub fn #router_helper_name() -> axum::routing::MethodRouter<()> {
             axum::routing::post(#wrapper_name)
+                #(#guard_layers)*
         }
 
         // Auto-registration using inventory
@@ -130,3 +140,44 @@ pub fn expand_action(item: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Collect `guard = path` entries from macro arguments
+pub fn parse_guards(
+    args: syn::punctuated::Punctuated<syn::Meta, syn::Token![,]>,
+) -> syn::Result<Vec<syn::Path>> {
+    let mut guards = Vec::new();
+    for arg in args {
+        match arg {
+            syn::Meta::NameValue(nv) if nv.path.is_ident("guard") => match nv.value {
+                syn::Expr::Path(expr_path) => guards.push(expr_path.path),
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "guard must be a path to an async middleware function, e.g. guard = auth::require_user",
+                    ))
+                }
+            },
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "Unknown action argument. Supported: guard = path::to::guard",
+                ))
+            }
+        }
+    }
+    Ok(guards)
+}
+
+/// Wrap guards as `.layer(...)` calls. Layers added last run first,
+/// so they are applied in reverse to keep declaration order.
+pub fn guard_layers(guards: &[syn::Path]) -> Vec<proc_macro2::TokenStream> {
+    guards
+        .iter()
+        .rev()
+        .map(|guard| {
+            quote! {
+                .layer(axum::middleware::from_fn(#guard))
+            }
+        })
+        .collect()
+}
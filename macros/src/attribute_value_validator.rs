@@ -0,0 +1,344 @@
+use crate::token_parser::{Attribute, AttributeValue, Element};
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+
+/// Rule 1: Enumerated attributes must use one of their keywords,
+/// and numeric attributes must be integers in range.
+/// Only static values are checked; dynamic {expr} values are trusted.
+pub fn validate_attribute_values(elem: &Element) -> Vec<TokenStream> {
+    let mut errors = vec![];
+
+    for attr in &elem.attrs {
+        let AttributeValue::Static(value) = &attr.value else {
+            continue;
+        };
+
+        let result = match (elem.name.as_str(), attr.name.as_str()) {
+            ("a" | "area" | "base" | "form", "target") => check_target(value),
+            ("form", "method") => check_keyword(value, FORM_METHODS),
+            ("a" | "area" | "link" | "form", "rel") => check_tokens(value, REL_VALUES),
+            ("input" | "select" | "textarea" | "form", "autocomplete") => check_autocomplete(value),
+            (_, "inputmode") => check_keyword(value, INPUTMODE_VALUES),
+            ("img" | "iframe", "loading") => check_keyword(value, LOADING_VALUES),
+            ("img" | "link" | "script" | "audio" | "video", "crossorigin") => {
+                check_keyword(value, CROSSORIGIN_VALUES)
+            }
+            (_, "dir") => check_keyword(value, DIR_VALUES),
+            (_, "tabindex") => check_integer(value, i64::from(i32::MIN), i64::from(i32::MAX)),
+            ("td" | "th", "colspan") => check_integer(value, 1, 1000),
+            ("td" | "th", "rowspan") => check_integer(value, 0, 65534),
+            ("textarea", "rows" | "cols") => check_integer(value, 1, i64::from(u32::MAX)),
+            _ => Ok(()),
+        };
+
+        if let Err(detail) = result {
+            errors.push(value_error(elem, attr, value, &detail));
+        }
+    }
+
+    errors
+}
+
+const FORM_METHODS: &[&str] = &["get", "post", "dialog"];
+
+const REL_VALUES: &[&str] = &[
+    "alternate",
+    "author",
+    "bookmark",
+    "canonical",
+    "dns-prefetch",
+    "external",
+    "help",
+    "icon",
+    "license",
+    "manifest",
+    "me",
+    "modulepreload",
+    "next",
+    "nofollow",
+    "noopener",
+    "noreferrer",
+    "opener",
+    "pingback",
+    "preconnect",
+    "prefetch",
+    "preload",
+    "prerender",
+    "prev",
+    "privacy-policy",
+    "search",
+    "stylesheet",
+    "tag",
+    "terms-of-service",
+    "apple-touch-icon",
+    "mask-icon",
+    // Registered extensions (microformats.org/wiki/existing-rel-values)
+    "shortcut",
+    "sponsored",
+    "ugc",
+    "expect",
+    "compression-dictionary",
+    "webmention",
+    "apple-touch-icon-precomposed",
+    "apple-touch-startup-image",
+    "archives",
+    "edit",
+    "enclosure",
+    "first",
+    "hub",
+    "index",
+    "last",
+    "profile",
+    "publisher",
+    "syndication",
+    "up",
+];
+
+const AUTOCOMPLETE_VALUES: &[&str] = &[
+    "on",
+    "off",
+    "name",
+    "honorific-prefix",
+    "given-name",
+    "additional-name",
+    "family-name",
+    "honorific-suffix",
+    "nickname",
+    "email",
+    "username",
+    "new-password",
+    "current-password",
+    "one-time-code",
+    "organization-title",
+    "organization",
+    "street-address",
+    "address-line1",
+    "address-line2",
+    "address-line3",
+    "address-level1",
+    "address-level2",
+    "address-level3",
+    "address-level4",
+    "country",
+    "country-name",
+    "postal-code",
+    "cc-name",
+    "cc-given-name",
+    "cc-additional-name",
+    "cc-family-name",
+    "cc-number",
+    "cc-exp",
+    "cc-exp-month",
+    "cc-exp-year",
+    "cc-csc",
+    "cc-type",
+    "transaction-currency",
+    "transaction-amount",
+    "language",
+    "bday",
+    "bday-day",
+    "bday-month",
+    "bday-year",
+    "sex",
+    "tel",
+    "tel-country-code",
+    "tel-national",
+    "tel-area-code",
+    "tel-local",
+    "tel-extension",
+    "impp",
+    "url",
+    "photo",
+    "webauthn",
+];
+
+// Hint tokens that may precede an autocomplete field name
+const AUTOCOMPLETE_HINTS: &[&str] = &[
+    "shipping", "billing", "home", "work", "mobile", "fax", "pager",
+];
+
+const INPUTMODE_VALUES: &[&str] = &[
+    "none", "text", "decimal", "numeric", "tel", "search", "email", "url",
+];
+
+const LOADING_VALUES: &[&str] = &["lazy", "eager"];
+
+const CROSSORIGIN_VALUES: &[&str] = &["", "anonymous", "use-credentials"];
+
+const DIR_VALUES: &[&str] = &["ltr", "rtl", "auto"];
+
+const TARGET_KEYWORDS: &[&str] = &["_self", "_blank", "_parent", "_top"];
+
+// Helper: Named browsing contexts are allowed; only `_keyword` values are checked
+fn check_target(value: &str) -> Result<(), String> {
+    if value.starts_with('_') {
+        check_keyword(value, TARGET_KEYWORDS)
+    } else {
+        Ok(())
+    }
+}
+
+// Helper: Single keyword from a fixed list (ASCII case-insensitive, as in HTML)
+fn check_keyword(value: &str, allowed: &[&str]) -> Result<(), String> {
+    if allowed
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(value))
+    {
+        Ok(())
+    } else {
+        Err(suggest_value(value, allowed))
+    }
+}
+
+// Helper: Space-separated keyword list (rel="noopener noreferrer")
+fn check_tokens(value: &str, allowed: &[&str]) -> Result<(), String> {
+    for token in value.split_whitespace() {
+        if !allowed.contains(&token.to_ascii_lowercase().as_str()) {
+            return Err(format!(
+                "'{}' is not allowed. {}",
+                token,
+                suggest_value(token, allowed)
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Helper: autocomplete="section-x shipping street-address"
+fn check_autocomplete(value: &str) -> Result<(), String> {
+    let tokens: Vec<&str> = value.split_whitespace().collect();
+    let Some((field, hints)) = tokens.split_last() else {
+        return Ok(());
+    };
+
+    for hint in hints {
+        let hint_lower = hint.to_ascii_lowercase();
+        if !hint_lower.starts_with("section-") && !AUTOCOMPLETE_HINTS.contains(&hint_lower.as_str())
+        {
+            return Err(format!(
+                "'{}' is not a valid autocomplete hint. Use section-*, {} before the field name.",
+                hint,
+                AUTOCOMPLETE_HINTS.join(", ")
+            ));
+        }
+    }
+
+    if AUTOCOMPLETE_VALUES.contains(&field.to_ascii_lowercase().as_str()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' is not a valid autocomplete field. {}",
+            field,
+            suggest_value(field, AUTOCOMPLETE_VALUES)
+        ))
+    }
+}
+
+// Helper: Integer within [min, max]
+fn check_integer(value: &str, min: i64, max: i64) -> Result<(), String> {
+    match value.trim().parse::<i64>() {
+        Ok(n) if n >= min && n <= max => Ok(()),
+        Ok(_) => Err(format!("Must be between {} and {}.", min, max)),
+        Err(_) => Err(format!(
+            "Must be an integer (e.g. \"{}\"). Use {{expr}} for computed values.",
+            min.max(0)
+        )),
+    }
+}
+
+// Helper: Suggest the closest keyword for typos
+fn suggest_value(value: &str, allowed: &[&str]) -> String {
+    let lower = value.to_ascii_lowercase();
+    let closest = allowed
+        .iter()
+        .filter(|keyword| !keyword.is_empty())
+        .map(|keyword| (edit_distance(&lower, keyword), *keyword))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance);
+
+    match closest {
+        Some((_, keyword)) => format!("Did you mean '{}'?", keyword),
+        None if allowed.len() <= 8 => format!(
+            "Valid values: {}",
+            allowed
+                .iter()
+                .map(|k| if k.is_empty() { "\"\"" } else { k })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None => "Check the attribute's allowed values on MDN.".to_string(),
+    }
+}
+
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr.push((prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1));
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+fn value_error(elem: &Element, attr: &Attribute, value: &str, detail: &str) -> TokenStream {
+    let msg = format!(
+        "Invalid <{}> {}=\"{}\". {}",
+        elem.name, attr.name, value, detail
+    );
+    let error_span = attr.value_span.unwrap_or(attr.span);
+    quote_spanned! { error_span =>
+        compile_error!(#msg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_suggestions() {
+        assert!(check_keyword("post", FORM_METHODS).is_ok());
+        assert!(check_keyword("POST", FORM_METHODS).is_ok());
+        assert!(check_keyword("RTL", DIR_VALUES).is_ok());
+        assert!(check_keyword("Lazy", LOADING_VALUES).is_ok());
+        assert_eq!(
+            check_keyword("lazzy", LOADING_VALUES).unwrap_err(),
+            "Did you mean 'lazy'?"
+        );
+        assert_eq!(
+            check_keyword("sideways", DIR_VALUES).unwrap_err(),
+            "Valid values: ltr, rtl, auto"
+        );
+        assert!(check_target("_blnk").unwrap_err().contains("'_blank'"));
+        assert!(check_target("preview_frame").is_ok());
+    }
+
+    #[test]
+    fn test_rel_and_autocomplete_tokens() {
+        assert!(check_tokens("noopener noreferrer", REL_VALUES).is_ok());
+        assert!(check_tokens("shortcut icon", REL_VALUES).is_ok());
+        assert!(check_tokens("nofollow Sponsored ugc", REL_VALUES).is_ok());
+        assert!(check_tokens("noopener nofolow", REL_VALUES)
+            .unwrap_err()
+            .contains("Did you mean 'nofollow'?"));
+        assert!(check_autocomplete("section-billing shipping postal-code").is_ok());
+        assert!(check_autocomplete("new-pasword")
+            .unwrap_err()
+            .contains("'new-password'"));
+        assert!(check_autocomplete("office email").is_err());
+    }
+
+    #[test]
+    fn test_numeric_values() {
+        assert!(check_integer("-1", i64::from(i32::MIN), i64::from(i32::MAX)).is_ok());
+        assert!(check_integer("two", 1, 1000).is_err());
+        assert_eq!(
+            check_integer("0", 1, 1000).unwrap_err(),
+            "Must be between 1 and 1000."
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! az-on DSL helpers
+//!
+//! Parses the target part of `az-on={click call foo -> #target swap transition}`
+//! so swap modes can be validated at compile time.
+
+/// Swap strategies understood by the client runtime
+pub const SWAP_MODES: &[&str] = &[
+    "morph",
+    "innerHTML",
+    "outerHTML",
+    "beforebegin",
+    "afterbegin",
+    "beforeend",
+    "afterend",
+    "delete",
+    "none",
+];
+
+/// Modifier that wraps the swap in `document.startViewTransition`
+pub const TRANSITION: &str = "transition";
+
+/// Everything after `->` in an az-on DSL string
+#[derive(Debug, PartialEq)]
+pub struct AzOnTarget {
+    pub target: String,
+    pub swap: Option<String>,
+    pub transition: bool,
+}
+
+/// Parse `# target innerHTML transition` (TokenStream spacing) into its parts
+pub fn parse_target(rest: &str) -> Result<AzOnTarget, String> {
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    if words.is_empty() {
+        return Err("az-on is missing a target after '->'. Example: -> #my_box".to_string());
+    }
+
+    // TokenStream::to_string() splits "#my_box" into "# my_box", so glue the selector back
+    let mut target = String::new();
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        let glue = target.is_empty()
+            || target.ends_with(['#', '.', '-'])
+            || word == "-"
+            || word.starts_with('-');
+        if !glue {
+            break;
+        }
+        target.push_str(word);
+        i += 1;
+    }
+
+    let mut swap = None;
+    let mut transition = false;
+    for word in &words[i..] {
+        if *word == TRANSITION {
+            transition = true;
+        } else if SWAP_MODES.contains(word) {
+            if let Some(previous) = &swap {
+                return Err(format!(
+                    "az-on has two swap modes ('{}' and '{}'). Use only one.",
+                    previous, word
+                ));
+            }
+            swap = Some(word.to_string());
+        } else {
+            return Err(unknown_swap_message(word));
+        }
+    }
+
+    Ok(AzOnTarget {
+        target,
+        swap,
+        transition,
+    })
+}
+
+/// Check an az-oob value (a swap mode, or empty for the default morph)
+pub fn validate_swap(mode: &str) -> Result<(), String> {
+    if mode.is_empty() || SWAP_MODES.contains(&mode) {
+        Ok(())
+    } else {
+        Err(unknown_swap_message(mode))
+    }
+}
+
+fn unknown_swap_message(word: &str) -> String {
+    let suggestion = SWAP_MODES
+        .iter()
+        .find(|mode| mode.eq_ignore_ascii_case(word))
+        .map(|mode| format!(" Did you mean '{}'?", mode))
+        .unwrap_or_default();
+    format!(
+        "Unknown swap mode '{}'.{} Valid modes: {}, optionally followed by '{}'.",
+        word,
+        suggestion,
+        SWAP_MODES.join(", "),
+        TRANSITION
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target_only() {
+        let parsed = parse_target(" # like_box").unwrap();
+        assert_eq!(parsed.target, "#like_box");
+        assert_eq!(parsed.swap, None);
+        assert!(!parsed.transition);
+    }
+
+    #[test]
+    fn test_parse_swap_and_transition() {
+        let parsed = parse_target("# list beforeend transition").unwrap();
+        assert_eq!(parsed.target, "#list");
+        assert_eq!(parsed.swap.as_deref(), Some("beforeend"));
+        assert!(parsed.transition);
+    }
+
+    #[test]
+    fn test_parse_unknown_swap() {
+        let err = parse_target("# list innerhtml").unwrap_err();
+        assert!(err.contains("Did you mean 'innerHTML'?"));
+        assert!(parse_target("# list append").is_err());
+        assert!(parse_target("# list delete none").is_err());
+    }
+}
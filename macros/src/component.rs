@@ -1,12 +1,39 @@
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::{parse_macro_input, FnArg, Item, ItemFn, Pat, PatType, Stmt};
 
-pub fn expand_component(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+pub fn expand_component(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    // #[azumi::component(classes)] also generates a `Classes` enum
+    let mut with_classes = false;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("classes") {
+            with_classes = true;
+            Ok(())
+        } else {
+            Err(meta.error("Unknown component option. Expected: #[azumi::component(classes)]"))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
+
     let input = parse_macro_input!(item as ItemFn);
+    let mut styles = Vec::new();
+    collect_html_styles(input.block.to_token_stream(), &mut styles);
+
+    let (classes_enum, used_classes) = if with_classes {
+        match classes_enum(&input, &styles) {
+            Ok(generated) => generated,
+            Err(err) => return err.to_compile_error().into(),
+        }
+    } else {
+        (quote! {}, Vec::new())
+    };
+    let exports = exports_module(&styles);
 
     let fn_name = &input.sig.ident;
     let fn_vis = &input.vis;
-    let fn_block = input.block;
+    let fn_block = mark_used_classes(input.block.to_token_stream(), &used_classes, false);
     let fn_output = &input.sig.output;
 
     // Parse arguments into props
@@ -254,6 +281,10 @@ pub fn expand_component(item: proc_macro::TokenStream) -> proc_macro::TokenStrea
                 }
             }
 
+            #classes_enum
+
+            #exports
+
             #render_fn
         }
 
@@ -262,3 +293,207 @@ pub fn expand_component(item: proc_macro::TokenStream) -> proc_macro::TokenStrea
 
     proc_macro::TokenStream::from(expanded)
 }
+
+/// `Classes` with a variant per class of the component's scoped <style>, so
+/// class choices can be matched on and passed to children as a type:
+/// `card_component::Classes::IsOpen.as_str() == "is_open-s1a2b"`.
+/// Also returns the classes whose variant the function mentions.
+fn classes_enum(
+    input: &ItemFn,
+    styles: &[proc_macro2::TokenStream],
+) -> syn::Result<(proc_macro2::TokenStream, Vec<String>)> {
+    use heck::ToUpperCamelCase;
+    use quote::format_ident;
+
+    // A later block's class shadows an earlier one's, like the bindings
+    let mut classes = std::collections::BTreeMap::new();
+    for style in styles {
+        classes.extend(crate::style::scoped_classes(style.clone()));
+    }
+    if classes.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.sig.ident,
+            "#[azumi::component(classes)] needs a <style> with classes in the component's html!",
+        ));
+    }
+
+    let variants: Vec<_> = classes
+        .keys()
+        .map(|class| format_ident!("{}", class.to_upper_camel_case()))
+        .collect();
+    let docs = classes.keys().map(|class| format!(".{}", class));
+    let scoped = classes.values();
+
+    let mut mentioned = std::collections::HashSet::new();
+    collect_idents(input.block.to_token_stream(), &mut mentioned);
+    let used = classes
+        .keys()
+        .zip(&variants)
+        .filter(|(_, variant)| mentioned.contains(&variant.to_string()))
+        .map(|(class, _)| class.clone())
+        .collect();
+
+    let classes_enum = quote! {
+        /// Scoped class names of this component's <style>
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Classes {
+            #(#[doc = #docs] #variants,)*
+        }
+
+        impl Classes {
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    #(Classes::#variants => #scoped,)*
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for Classes {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+    Ok((classes_enum, used))
+}
+
+/// `exports::CHIP` for each class the component's <style> shares with
+/// `@export { .chip { ... } }`, for `composes: chip from this_component;`
+fn exports_module(styles: &[proc_macro2::TokenStream]) -> proc_macro2::TokenStream {
+    use heck::ToShoutySnakeCase;
+    use quote::format_ident;
+
+    let mut sheets = Vec::new();
+    let mut constants = Vec::new();
+    for (i, style) in styles.iter().enumerate() {
+        let Some(exported) = crate::style::exported_styles(style.clone()) else {
+            continue;
+        };
+        // The same id and CSS as the exporting html! registers; it reports errors
+        let Ok(Some((id, css))) = crate::lowered_stylesheet(&exported.css, true) else {
+            continue;
+        };
+        let sheet = format_ident!("SHEET_{}", i);
+        sheets.push(quote! {
+            const #sheet: azumi::styles::Stylesheet = azumi::styles::Stylesheet {
+                id: #id,
+                css: #css,
+                global: true,
+            };
+        });
+        for (class, scoped) in exported.classes {
+            let name = format_ident!("{}", class.to_shouty_snake_case());
+            let doc = format!(".{}", class);
+            constants.push(quote! {
+                #[doc = #doc]
+                pub const #name: azumi::styles::SharedClass = azumi::styles::SharedClass {
+                    class: #scoped,
+                    sheet: &#sheet,
+                };
+            });
+        }
+    }
+    if constants.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        /// Classes this component's <style> shares with @export
+        pub mod exports {
+            #(#sheets)*
+            #(#constants)*
+        }
+    }
+}
+
+fn collect_idents(
+    tokens: proc_macro2::TokenStream,
+    idents: &mut std::collections::HashSet<String>,
+) {
+    for tt in tokens {
+        match tt {
+            proc_macro2::TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
+            }
+            proc_macro2::TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
+
+// Classes picked through `Classes::X` outside the template count as used for
+// html!'s unused-selector warnings: add `@used(x);` after each `<style>` tag.
+// @used writes no CSS, so the scoped class names don't change.
+fn mark_used_classes(
+    tokens: proc_macro2::TokenStream,
+    used: &[String],
+    in_html: bool,
+) -> proc_macro2::TokenStream {
+    use proc_macro2::{Group, TokenTree};
+
+    if used.is_empty() {
+        return tokens;
+    }
+    let names = used.iter().map(|class| quote::format_ident!("{}", class));
+    let used_rule = quote! { @used(#(#names),*); };
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut output = Vec::with_capacity(tokens.len());
+    let mut in_style_tag = false;
+    for (i, tt) in tokens.iter().enumerate() {
+        match tt {
+            TokenTree::Group(group) => {
+                let is_html = i >= 2
+                    && matches!(&tokens[i - 1], TokenTree::Punct(p) if p.as_char() == '!')
+                    && matches!(&tokens[i - 2], TokenTree::Ident(name) if name == "html");
+                let stream = mark_used_classes(group.stream(), used, in_html || is_html);
+                let mut marked = Group::new(group.delimiter(), stream);
+                marked.set_span(group.span());
+                output.push(TokenTree::Group(marked));
+            }
+            TokenTree::Ident(name) if in_html && name == "style" => {
+                in_style_tag = matches!(
+                    i.checked_sub(1).map(|prev| &tokens[prev]),
+                    Some(TokenTree::Punct(p)) if p.as_char() == '<'
+                );
+                output.push(tt.clone());
+            }
+            TokenTree::Punct(p) if in_style_tag && p.as_char() == '>' => {
+                in_style_tag = false;
+                output.push(tt.clone());
+                // Not for a self-closing <style src="..." />
+                if !matches!(&tokens[i - 1], TokenTree::Punct(p) if p.as_char() == '/') {
+                    output.extend(used_rule.clone());
+                }
+            }
+            _ => output.push(tt.clone()),
+        }
+    }
+    output.into_iter().collect()
+}
+
+// Contents of the scoped <style> blocks in every html! { ... } of the function
+fn collect_html_styles(
+    tokens: proc_macro2::TokenStream,
+    styles: &mut Vec<proc_macro2::TokenStream>,
+) {
+    use proc_macro2::TokenTree;
+    use syn::parse::Parser;
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, tt) in tokens.iter().enumerate() {
+        let TokenTree::Group(group) = tt else {
+            continue;
+        };
+        let is_html = i >= 2
+            && matches!(&tokens[i - 1], TokenTree::Punct(p) if p.as_char() == '!')
+            && matches!(&tokens[i - 2], TokenTree::Ident(name) if name == "html");
+        if !is_html {
+            collect_html_styles(group.stream(), styles);
+            continue;
+        }
+        // Template errors are reported by html! itself
+        if let Ok(nodes) = crate::token_parser::parse_nodes.parse2(group.stream()) {
+            crate::style::scoped_style_blocks(&nodes, styles);
+        }
+    }
+}
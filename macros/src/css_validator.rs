@@ -69,16 +69,9 @@ fn extract_html_classes_recursive(nodes: &[Node], used_classes: &mut HashMap<Str
                                 }
                             }
                         }
-                        AttributeValue::Dynamic(_expr) => {
-                            // For dynamic expressions, we can't validate at compile time
-                            // but we should warn about this
-                            eprintln!("Warning: Dynamic class attribute detected. Cannot validate at compile time.");
-                        }
-                        AttributeValue::StyleDsl(_) => {
-                            // Style DSL is not valid for class attribute
-                            eprintln!("Warning: Style DSL used in class attribute. This is invalid.");
-                        }
-                        AttributeValue::None => {}
+                        // Dynamic expressions can't be checked at compile time, and a
+                        // style DSL in class= is rejected by the attribute validator
+                        AttributeValue::Dynamic(_) | AttributeValue::StyleDsl(_) | AttributeValue::None => {}
                     }
                 }
                 
@@ -97,6 +90,9 @@ fn extract_html_classes_recursive(nodes: &[Node], used_classes: &mut HashMap<Str
                 }
                 Block::For(for_block) => {
                     extract_html_classes_recursive(&for_block.body, used_classes);
+                    if let Some(empty_branch) = &for_block.empty_branch {
+                        extract_html_classes_recursive(empty_branch, used_classes);
+                    }
                 }
                 Block::Match(match_block) => {
                     for arm in &match_block.arms {
@@ -186,9 +182,13 @@ pub fn validate_component_css(nodes: &[Node]) -> proc_macro2::TokenStream {
     }
 
     if !css_files.is_empty() {
-        return quote! {
-            compile_error!("External CSS files are banned in Azumi. Use the style! macro instead.");
-        };
+        return css_files
+            .iter()
+            .map(|(_, span)| {
+                syn::Error::new(*span, "External CSS files are banned in Azumi. Use the style! macro instead.")
+                    .to_compile_error()
+            })
+            .collect();
     }
 
     quote! {}
@@ -231,6 +231,9 @@ Why? External files get full IDE support (linting, autocomplete, error checking)
                 }
                 Block::For(for_block) => {
                     check_inline_styles_recursive(&for_block.body, errors);
+                    if let Some(empty_branch) = &for_block.empty_branch {
+                        check_inline_styles_recursive(empty_branch, errors);
+                    }
                 }
                 Block::Match(match_block) => {
                     for arm in &match_block.arms {
@@ -247,8 +250,8 @@ Why? External files get full IDE support (linting, autocomplete, error checking)
     }
 }
 
-/// Collect all CSS file paths from <style src="..."> tags
-fn collect_css_files(nodes: &[Node], css_files: &mut Vec<String>) {
+/// Collect all CSS file paths from <style src="..."> tags, with the span of the src attribute
+fn collect_css_files(nodes: &[Node], css_files: &mut Vec<(String, proc_macro2::Span)>) {
     for node in nodes {
         match node {
             Node::Element(elem) => {
@@ -259,7 +262,7 @@ fn collect_css_files(nodes: &[Node], css_files: &mut Vec<String>) {
                             if !path.ends_with("global.css") {
                                 // Enhanced path resolution for demo projects
                                 let css_file_path = resolve_css_file_path(path);
-                                css_files.push(css_file_path);
+                                css_files.push((css_file_path, src_attr.span));
                             }
                         }
                     }
@@ -279,6 +282,9 @@ fn collect_css_files(nodes: &[Node], css_files: &mut Vec<String>) {
                 }
                 Block::For(for_block) => {
                     collect_css_files(&for_block.body, css_files);
+                    if let Some(empty_branch) = &for_block.empty_branch {
+                        collect_css_files(empty_branch, css_files);
+                    }
                 }
                 Block::Match(match_block) => {
                     for arm in &match_block.arms {
@@ -304,7 +310,7 @@ pub fn resolve_css_file_path(css_path: &str) -> String {
     let clean_path = css_path.trim_start_matches('/');
     
     // Handle different project structures
-    let possible_paths = vec![
+    let possible_paths = [
         // 1. Direct path from manifest dir (e.g. demo/static/style.css)
         manifest_path.join(clean_path).to_string_lossy().to_string(),
         
@@ -327,23 +333,187 @@ pub fn resolve_css_file_path(css_path: &str) -> String {
         manifest_path.join("src").join(clean_path).to_string_lossy().to_string(),
     ];
     
-    eprintln!("🔍 Resolving CSS path: '{}' from '{}'", css_path, manifest_dir);
-    
     // Try each possible path and return the first one that exists
-    for path in &possible_paths {
-        if std::path::Path::new(path).exists() {
-            eprintln!("✅ Found CSS file: {}", path);
-            return path.clone();
-        } else {
-            // eprintln!("  Checked: {}", path); // Uncomment for verbose debugging
-        }
+    if let Some(path) = possible_paths.iter().find(|path| std::path::Path::new(path).exists()) {
+        return path.clone();
     }
     
     // If no file found, return the first constructed path (relative to manifest)
     // This ensures the error message shows a reasonable path
-    let default_path = manifest_path.join(clean_path).to_string_lossy().to_string();
-    eprintln!("⚠️  CSS file not found. Checked {} locations.", possible_paths.len());
-    default_path
+    manifest_path.join(clean_path).to_string_lossy().to_string()
+}
+
+/// Warn about classes and ids in the component's scoped <style> that nothing in
+/// the template refers to. A class counts as used when its binding appears in
+/// any expression (`class={if open { active } else { closed }}`, `@child(class = card)`),
+/// when it is written literally or toggled with `az-bind:class:name`, or when
+/// the style lists it in `@used(...)` because it is only chosen at runtime.
+pub fn warn_unused_selectors(nodes: &[Node]) -> proc_macro2::TokenStream {
+    let mut selectors = crate::style::StyleSelectors::default();
+    collect_scoped_selectors(nodes, &mut selectors);
+    if selectors.classes.is_empty() && selectors.ids.is_empty() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let mut referenced = HashSet::new();
+    collect_template_names(nodes, &mut referenced);
+
+    let mut output = proc_macro2::TokenStream::new();
+    let defined: HashSet<&str> = selectors
+        .classes
+        .iter()
+        .chain(&selectors.ids)
+        .map(|(name, _)| name.as_str())
+        .collect();
+    for (name, span) in &selectors.used {
+        if !defined.contains(name.as_str()) {
+            let msg = format!(
+                "@used names '{}', which is not a class or id in this component's <style>",
+                name
+            );
+            output.extend(quote::quote_spanned! { *span => compile_error!(#msg); });
+        }
+        referenced.insert(name.clone());
+    }
+
+    let mut reported = HashSet::new();
+    for (kind, names) in [("class", &selectors.classes), ("id", &selectors.ids)] {
+        for (name, span) in names {
+            if referenced.contains(name) || !reported.insert(name) {
+                continue;
+            }
+            let msg = format!(
+                "CSS {} '{}' is never used in this component. Remove the rule, or list it in `@used({})` if it is picked at runtime.",
+                kind, name, name
+            );
+            output.extend(crate::compile_warning(*span, &msg));
+        }
+    }
+    output
+}
+
+fn collect_scoped_selectors(nodes: &[Node], selectors: &mut crate::style::StyleSelectors) {
+    for node in nodes {
+        match node {
+            Node::Element(elem) => collect_scoped_selectors(&elem.children, selectors),
+            Node::Fragment(frag) => collect_scoped_selectors(&frag.children, selectors),
+            Node::Block(block) => match block {
+                // Global styles are opt-out, like for class validation
+                Block::Style(style) if !style.is_global => {
+                    crate::style::style_selectors(style.content.clone(), selectors)
+                }
+                Block::If(if_block) => {
+                    collect_scoped_selectors(&if_block.then_branch, selectors);
+                    if let Some(else_branch) = &if_block.else_branch {
+                        collect_scoped_selectors(else_branch, selectors);
+                    }
+                }
+                Block::For(for_block) => {
+                    collect_scoped_selectors(&for_block.body, selectors);
+                    if let Some(empty_branch) = &for_block.empty_branch {
+                        collect_scoped_selectors(empty_branch, selectors);
+                    }
+                }
+                Block::Match(match_block) => {
+                    for arm in &match_block.arms {
+                        collect_scoped_selectors(&arm.body, selectors);
+                    }
+                }
+                Block::Call(call_block) => {
+                    collect_scoped_selectors(&call_block.children, selectors)
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+// Every identifier in the template's Rust expressions (a selector's binding has
+// its name) plus the words of static class/id values and string literals
+fn collect_template_names(nodes: &[Node], names: &mut HashSet<String>) {
+    for node in nodes {
+        match node {
+            Node::Element(elem) => {
+                for attr in &elem.attrs {
+                    // az-bind:class:active="..." toggles `active` on the client
+                    if let Some(class) = attr.name.strip_prefix("az-bind:class:") {
+                        names.insert(class.to_string());
+                    }
+                    match &attr.value {
+                        AttributeValue::Static(value) if attr.name == "class" || attr.name == "id" => {
+                            names.extend(value.split_whitespace().map(str::to_string));
+                        }
+                        AttributeValue::Dynamic(tokens) => collect_token_names(tokens, names),
+                        AttributeValue::StyleDsl(properties) => {
+                            for (_, value) in properties {
+                                collect_token_names(value, names);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                for spread in &elem.spreads {
+                    collect_token_names(spread, names);
+                }
+                collect_template_names(&elem.children, names);
+            }
+            Node::Expression(expr) => collect_token_names(&expr.content, names),
+            Node::Fragment(frag) => collect_template_names(&frag.children, names),
+            Node::Block(block) => match block {
+                Block::If(if_block) => {
+                    collect_token_names(&if_block.condition, names);
+                    collect_template_names(&if_block.then_branch, names);
+                    if let Some(else_branch) = &if_block.else_branch {
+                        collect_template_names(else_branch, names);
+                    }
+                }
+                Block::For(for_block) => {
+                    collect_token_names(&for_block.iterator, names);
+                    collect_template_names(&for_block.body, names);
+                    if let Some(empty_branch) = &for_block.empty_branch {
+                        collect_template_names(empty_branch, names);
+                    }
+                }
+                Block::Match(match_block) => {
+                    collect_token_names(&match_block.expr, names);
+                    for arm in &match_block.arms {
+                        collect_token_names(&arm.pattern, names);
+                        collect_template_names(&arm.body, names);
+                    }
+                }
+                Block::Call(call_block) => {
+                    collect_token_names(&call_block.args, names);
+                    collect_template_names(&call_block.children, names);
+                }
+                Block::Let(let_block) => collect_token_names(&let_block.value, names),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+fn collect_token_names(tokens: &proc_macro2::TokenStream, names: &mut HashSet<String>) {
+    use proc_macro2::TokenTree;
+    for tt in tokens.clone() {
+        match tt {
+            // `Classes::IsOpen` refers to `is_open`
+            TokenTree::Ident(ident) => {
+                use heck::ToSnakeCase;
+                let name = ident.to_string();
+                names.insert(name.to_snake_case());
+                names.insert(name);
+            }
+            TokenTree::Group(group) => collect_token_names(&group.stream(), names),
+            TokenTree::Literal(lit) => {
+                if let Ok(lit) = syn::parse2::<syn::LitStr>(TokenTree::Literal(lit).into()) {
+                    names.extend(lit.value().split_whitespace().map(str::to_string));
+                }
+            }
+            TokenTree::Punct(_) => {}
+        }
+    }
 }
 
 #[cfg(test)]
@@ -407,4 +577,38 @@ mod tests {
         let result = validate_css_classes(&used, &defined);
         assert!(result.is_ok()); // Should be OK now, as dead CSS is handled separately
     }
+
+    #[test]
+    fn test_warn_unused_selectors() {
+        use syn::parse::Parser;
+
+        // `#main` can't be written inside quote!
+        let input: proc_macro2::TokenStream = r#"
+            <style>
+                .card { padding: "1rem"; }
+                .is_open { display: "block"; }
+                .stale { color: "red"; }
+                #main { margin: "0"; }
+                @used(is_open);
+            </style>
+            <div id={main} class={card}>"x"</div>
+        "#
+        .parse()
+        .unwrap();
+        let nodes = crate::token_parser::parse_nodes.parse2(input).unwrap();
+        let output = warn_unused_selectors(&nodes).to_string();
+        assert!(output.contains("CSS class 'stale' is never used"));
+        assert!(!output.contains("'card'"));
+        assert!(!output.contains("'is_open'"));
+        assert!(!output.contains("'main'"));
+
+        let nodes = crate::token_parser::parse_nodes
+            .parse2(quote::quote! {
+                <style> .card { padding: "1rem"; } @used(crad); </style>
+                <div class={card}>"x"</div>
+            })
+            .unwrap();
+        let output = warn_unused_selectors(&nodes).to_string();
+        assert!(output.contains("@used names 'crad'"));
+    }
 }
@@ -0,0 +1,96 @@
+//! `AZUMI_DEBUG=1` writes what the style macros expand to, one file per <style>
+//! block, under `target/azumi-debug/`. Nothing is printed during normal builds.
+
+use proc_macro2::{TokenStream, TokenTree};
+use std::path::{Path, PathBuf};
+
+const DEBUG_VAR: &str = "AZUMI_DEBUG";
+const DUMP_DIR: &str = "azumi-debug";
+
+/// Whether AZUMI_DEBUG is set to anything but empty or "0"
+fn enabled() -> bool {
+    std::env::var(DEBUG_VAR).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// `$CARGO_TARGET_DIR/azumi-debug`, or else the nearest `target` directory above
+/// the crate's manifest (the workspace's for workspace members)
+fn dump_dir(target_dir: Option<PathBuf>, manifest_dir: &Path) -> PathBuf {
+    let target = target_dir.unwrap_or_else(|| {
+        manifest_dir
+            .ancestors()
+            .map(|dir| dir.join("target"))
+            .find(|dir| dir.is_dir())
+            .unwrap_or_else(|| manifest_dir.join("target"))
+    });
+    target.join(DUMP_DIR)
+}
+
+/// The dump of one style block: a header naming it, its bindings and its CSS
+fn dump_contents(krate: &str, name: &str, css: &str, bindings: &TokenStream) -> String {
+    // One `let` per line, whatever line breaks the token printer chose
+    let mut lines = Vec::new();
+    let mut statement = TokenStream::new();
+    for token in bindings.clone() {
+        let ends = matches!(&token, TokenTree::Punct(p) if p.as_char() == ';');
+        statement.extend([token]);
+        if ends {
+            let text = statement.to_string();
+            lines.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+            statement = TokenStream::new();
+        }
+    }
+    let bindings = lines.join("\n");
+    format!(
+        "/* azumi: <style> block {} in crate {} */\n\n/* bindings:\n{}\n*/\n\n{}\n",
+        name, krate, bindings, css
+    )
+}
+
+/// Write a style block's expanded CSS and bindings to
+/// `target/azumi-debug/<crate>-<name>.css` when AZUMI_DEBUG is set.
+/// Best effort: a dump that can't be written never fails the build.
+pub fn dump_style(name: &str, css: &str, bindings: &TokenStream) {
+    if !enabled() {
+        return;
+    }
+    let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR") else {
+        return;
+    };
+    let dir = dump_dir(
+        std::env::var_os("CARGO_TARGET_DIR").map(PathBuf::from),
+        Path::new(&manifest_dir),
+    );
+    let krate = std::env::var("CARGO_CRATE_NAME").unwrap_or_else(|_| "unknown".to_string());
+    let path = dir.join(format!("{}-{}.css", krate, name));
+    let _ = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(path, dump_contents(&krate, name, css, bindings)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn test_dump_dir() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert_eq!(
+            dump_dir(Some(PathBuf::from("/tmp/build")), manifest),
+            Path::new("/tmp/build/azumi-debug")
+        );
+
+        // Without CARGO_TARGET_DIR the workspace's target directory is found
+        let dir = dump_dir(None, manifest);
+        assert!(dir.ends_with("target/azumi-debug"));
+        assert!(manifest.starts_with(dir.parent().unwrap().parent().unwrap()));
+    }
+
+    #[test]
+    fn test_dump_contents() {
+        let bindings = quote! { let card = "card-s1"; let title = "title-s1"; };
+        let dump = dump_contents("demo", "s1", ".card-s1 { color: red; }", &bindings);
+        assert!(dump.starts_with("/* azumi: <style> block s1 in crate demo */"));
+        assert!(dump.contains("let card = \"card-s1\" ;\nlet title = \"title-s1\" ;"));
+        assert!(dump.ends_with(".card-s1 { color: red; }\n"));
+    }
+}
@@ -0,0 +1,372 @@
+//! t!("key", name = expr): translations checked against the message catalogs
+//!
+//! Catalogs live in `locales/` next to Cargo.toml, one file per locale:
+//! `en.json` ({"greeting": "Hello, {name}!", "nav": {"home": "Home"}})
+//! or `fr.ftl` (greeting = Bonjour, { $name } !). Every key must exist in
+//! every catalog, and the arguments passed must match the placeholders.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Expr, Ident, LitStr, Token};
+
+const LOCALES_DIR: &str = "locales";
+
+struct Catalog {
+    locale: String,
+    file: String,      // e.g. "fr.ftl", for error messages
+    full_path: String, // for include_str! change tracking
+    messages: BTreeMap<String, String>,
+}
+
+// The files of a locales/ directory with their modification times
+type Stamps = Vec<(PathBuf, Option<SystemTime>)>;
+type CatalogCache = BTreeMap<PathBuf, (Stamps, Arc<Vec<Catalog>>)>;
+
+// Parsed catalogs per locales/ directory, reused by every t! in the crate until a
+// file changes (the macro stays loaded across edits in rust-analyzer)
+static CATALOGS: Mutex<CatalogCache> = Mutex::new(BTreeMap::new());
+
+struct TranslateInput {
+    key: LitStr,
+    args: Vec<(Ident, Expr)>,
+}
+
+impl Parse for TranslateInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: LitStr = input.parse()?;
+        let mut args = Vec::new();
+        while input.parse::<Token![,]>().is_ok() && !input.is_empty() {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: Expr = input.parse()?;
+            args.push((name, value));
+        }
+        if !input.is_empty() {
+            return Err(input.error("Expected t!(\"key\", name = value, ...)"));
+        }
+        Ok(TranslateInput { key, args })
+    }
+}
+
+pub fn expand_translate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as TranslateInput);
+    let key = input.key.value();
+
+    let catalogs = match load_catalogs() {
+        Ok(catalogs) => catalogs,
+        Err(msg) => {
+            return syn::Error::new(input.key.span(), msg)
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    // Rule 1: The key exists in every catalog
+    let missing: Vec<&str> = catalogs
+        .iter()
+        .filter(|c| !c.messages.contains_key(&key))
+        .map(|c| c.file.as_str())
+        .collect();
+    if !missing.is_empty() {
+        let msg = match suggest_key(&key, &catalogs) {
+            Some(similar) => format!(
+                "Translation key '{}' is missing from {}. Did you mean '{}'?",
+                key,
+                missing.join(", "),
+                similar
+            ),
+            None => format!(
+                "Translation key '{}' is missing from {}",
+                key,
+                missing.join(", ")
+            ),
+        };
+        return syn::Error::new(input.key.span(), msg)
+            .to_compile_error()
+            .into();
+    }
+
+    // Rule 2: Arguments match the placeholders used by any translation
+    let mut used: BTreeMap<String, &str> = BTreeMap::new();
+    for catalog in catalogs.iter() {
+        for name in placeholders(&catalog.messages[&key]) {
+            used.entry(name).or_insert(&catalog.file);
+        }
+    }
+    let provided: BTreeSet<String> = input.args.iter().map(|(n, _)| n.to_string()).collect();
+    for (name, _) in &input.args {
+        if !used.contains_key(&name.to_string()) {
+            let msg = format!(
+                "Translation '{}' has no {{{}}} placeholder. Remove the argument or add it to the catalogs.",
+                key, name
+            );
+            return syn::Error::new(name.span(), msg).to_compile_error().into();
+        }
+    }
+    if let Some((name, file)) = used.iter().find(|(name, _)| !provided.contains(*name)) {
+        let msg = format!(
+            "Translation '{}' needs argument '{}' (used in {}). Pass it as t!(\"{}\", {} = ...)",
+            key, name, file, key, name
+        );
+        return syn::Error::new(input.key.span(), msg)
+            .to_compile_error()
+            .into();
+    }
+
+    let file_deps = catalogs.iter().map(|c| {
+        let path = &c.full_path;
+        quote! { const _: &str = include_str!(#path); }
+    });
+    let translations = catalogs.iter().map(|c| {
+        let locale = &c.locale;
+        let text = &c.messages[&key];
+        quote! { (#locale, #text) }
+    });
+    let args = input.args.iter().map(|(name, value)| {
+        let name = name.to_string();
+        quote! { (#name, &(#value) as &dyn ::std::fmt::Display) }
+    });
+
+    quote! {
+        {
+            #(#file_deps)*
+            azumi::i18n::Message {
+                key: #key,
+                translations: &[#(#translations),*],
+            }
+            .format(&[#(#args),*])
+        }
+    }
+    .into()
+}
+
+fn load_catalogs() -> Result<Arc<Vec<Catalog>>, String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|e| e.to_string())?;
+    let dir = Path::new(&manifest_dir).join(LOCALES_DIR);
+    let entries = std::fs::read_dir(&dir).map_err(|e| {
+        format!(
+            "Cannot read translation catalogs from '{}': {}. Add {}/en.json (or .ftl) next to Cargo.toml.",
+            dir.display(),
+            e,
+            LOCALES_DIR
+        )
+    })?;
+    let mut stamps: Stamps = entries
+        .flatten()
+        .map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok();
+            (entry.path(), modified)
+        })
+        .collect();
+    stamps.sort();
+
+    let mut cache = CATALOGS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_stamps, catalogs)) = cache.get(&dir) {
+        if *cached_stamps == stamps {
+            return Ok(catalogs.clone());
+        }
+    }
+    let catalogs = Arc::new(parse_catalogs(&dir, &stamps)?);
+    cache.insert(dir, (stamps, catalogs.clone()));
+    Ok(catalogs)
+}
+
+fn parse_catalogs(dir: &Path, stamps: &Stamps) -> Result<Vec<Catalog>, String> {
+    let mut catalogs = Vec::new();
+    for (path, _) in stamps {
+        let (Some(stem), Some(ext)) = (path.file_stem(), path.extension()) else {
+            continue;
+        };
+        let file = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let source = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", file, e))?;
+        let messages = match ext.to_str() {
+            Some("json") => parse_json(&source),
+            Some("ftl") => parse_fluent(&source),
+            _ => continue,
+        }
+        .map_err(|e| format!("{}: {}", file, e))?;
+
+        catalogs.push(Catalog {
+            locale: stem.to_string_lossy().to_string(),
+            file,
+            full_path: path.to_string_lossy().to_string(),
+            messages,
+        });
+    }
+
+    if catalogs.is_empty() {
+        return Err(format!(
+            "No .json or .ftl catalogs found in '{}'",
+            dir.display()
+        ));
+    }
+    catalogs.sort_by(|a, b| a.locale.cmp(&b.locale));
+    Ok(catalogs)
+}
+
+// Helper: Nested objects become dotted keys ({"nav": {"home": ..}} -> "nav.home")
+fn parse_json(source: &str) -> Result<BTreeMap<String, String>, String> {
+    fn flatten(
+        prefix: &str,
+        value: &serde_json::Value,
+        out: &mut BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        match value {
+            serde_json::Value::String(text) => {
+                out.insert(prefix.to_string(), text.clone());
+            }
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    flatten(&key, value, out)?;
+                }
+            }
+            _ => return Err(format!("'{}' must be a string or an object", prefix)),
+        }
+        Ok(())
+    }
+
+    let value: serde_json::Value = serde_json::from_str(source).map_err(|e| e.to_string())?;
+    let mut out = BTreeMap::new();
+    flatten("", &value, &mut out)?;
+    Ok(out)
+}
+
+// Helper: The Fluent subset we support - messages, multiline values and { $var }
+fn parse_fluent(source: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut out: BTreeMap<String, String> = BTreeMap::new();
+    let mut current: Option<String> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            // Continuation of the previous message
+            let Some(key) = &current else {
+                return Err(format!("line {}: indented text outside a message", i + 1));
+            };
+            if trimmed.starts_with('.') || trimmed.starts_with('*') || trimmed.starts_with('[') {
+                return Err(format!(
+                    "line {}: Fluent attributes and selectors are not supported",
+                    i + 1
+                ));
+            }
+            let text = out.get_mut(key).expect("current message exists");
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&fluent_placeables(trimmed));
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected `key = value`", i + 1));
+        };
+        let key = key.trim();
+        if key.starts_with('-') {
+            return Err(format!("line {}: Fluent terms are not supported", i + 1));
+        }
+        out.insert(key.to_string(), fluent_placeables(value.trim()));
+        current = Some(key.to_string());
+    }
+
+    Ok(out)
+}
+
+// { $name } -> {name}
+fn fluent_placeables(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let inner = rest[start + 1..start + len].trim();
+        match inner.strip_prefix('$') {
+            Some(name) => out.push_str(&format!("{{{}}}", name.trim())),
+            None => out.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `{name}` placeholders in a message
+fn placeholders(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        if let Some(end) = rest.find('}') {
+            let name = &rest[..end];
+            if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+fn suggest_key<'a>(key: &str, catalogs: &'a [Catalog]) -> Option<&'a str> {
+    use crate::attribute_value_validator::edit_distance;
+    catalogs
+        .first()?
+        .messages
+        .keys()
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_flattens_keys() {
+        let messages =
+            parse_json(r#"{"greeting": "Hello, {name}!", "nav": {"home": "Home"}}"#).unwrap();
+        assert_eq!(messages["greeting"], "Hello, {name}!");
+        assert_eq!(messages["nav.home"], "Home");
+        assert!(parse_json(r#"{"count": 3}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_fluent() {
+        let messages = parse_fluent(
+            "# Comment\ngreeting = Bonjour, { $name } !\nintro =\n    Première ligne\n    suite\n",
+        )
+        .unwrap();
+        assert_eq!(messages["greeting"], "Bonjour, {name} !");
+        assert_eq!(messages["intro"], "Première ligne suite");
+        assert!(parse_fluent("-brand = Azumi").is_err());
+    }
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(
+            placeholders("{count} items for {name}, {not valid}"),
+            ["count", "name"]
+        );
+    }
+}
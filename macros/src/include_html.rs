@@ -0,0 +1,137 @@
+//! include_html!("templates/page.azumi"): html! templates in their own files
+//!
+//! The file holds exactly what would go inside html! { ... }. It is parsed
+//! with the same token_parser and validators, and tracked with include_str!
+//! so edits trigger a rebuild.
+
+use crate::token_parser::parse_nodes;
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenTree};
+use quote::quote;
+use syn::parse::Parser;
+use syn::{parse_macro_input, LitStr};
+
+pub fn expand_include_html(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    let (source, full_path) = match crate::read_manifest_file(&path.value()) {
+        Ok(file) => file,
+        Err(msg) => return syn::Error::new(path.span(), msg).to_compile_error().into(),
+    };
+
+    // Compiler spans can't point into another file, so errors are located with
+    // a separate pass over proc-macro2's fallback lexer, which tracks positions
+    let errors = locate_errors(&source);
+    if !errors.is_empty() {
+        let errors = errors.into_iter().map(|(line, column, msg)| {
+            let msg = format!("{}:{}:{}: {}", path.value(), line, column, msg);
+            quote::quote_spanned! { path.span() => compile_error!(#msg); }
+        });
+        return quote! { { #(#errors)* } }.into();
+    }
+
+    let tokens: proc_macro2::TokenStream = match source.parse() {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            return syn::Error::new(path.span(), e.to_string())
+                .to_compile_error()
+                .into()
+        }
+    };
+    let nodes = match parse_nodes.parse2(tokens) {
+        Ok(nodes) => nodes,
+        Err(e) => {
+            return syn::Error::new(path.span(), e.to_string())
+                .to_compile_error()
+                .into()
+        }
+    };
+    let file_dep = quote! { const _: &str = include_str!(#full_path); };
+    crate::expand_html(nodes, vec![file_dep])
+}
+
+/// Parse and validate the template with located spans.
+/// Returns (line, column, message) for every error, 1-based.
+fn locate_errors(source: &str) -> Vec<(usize, usize, String)> {
+    let _fallback = FallbackGuard::force();
+
+    let tokens: proc_macro2::TokenStream = match source.parse() {
+        Ok(tokens) => tokens,
+        Err(e) => return vec![located(e.span(), e.to_string())],
+    };
+    let nodes = match parse_nodes.parse2(tokens) {
+        Ok(nodes) => nodes,
+        Err(e) => {
+            return e
+                .into_iter()
+                .map(|e| located(e.span(), e.to_string()))
+                .collect()
+        }
+    };
+
+    // Validators report through compile_error! in the generated code
+    let mut errors = Vec::new();
+    collect_compile_errors(crate::generate_nodes(&nodes), &mut errors);
+    errors
+}
+
+fn located(span: Span, msg: String) -> (usize, usize, String) {
+    let start = span.start();
+    (start.line, start.column + 1, msg)
+}
+
+// Finds `compile_error!("msg")` invocations, at any depth
+fn collect_compile_errors(
+    tokens: proc_macro2::TokenStream,
+    errors: &mut Vec<(usize, usize, String)>,
+) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, tt) in tokens.iter().enumerate() {
+        match tt {
+            TokenTree::Ident(ident) if ident == "compile_error" => {
+                let Some(TokenTree::Group(args)) = tokens.get(i + 2) else {
+                    continue;
+                };
+                if let Ok(msg) = syn::parse2::<LitStr>(args.stream()) {
+                    errors.push(located(ident.span(), msg.value()));
+                }
+            }
+            TokenTree::Group(group) => collect_compile_errors(group.stream(), errors),
+            _ => {}
+        }
+    }
+}
+
+// Restores the compiler's span implementation even if validation panics
+struct FallbackGuard;
+
+impl FallbackGuard {
+    fn force() -> Self {
+        proc_macro2::fallback::force();
+        FallbackGuard
+    }
+}
+
+impl Drop for FallbackGuard {
+    fn drop(&mut self) {
+        proc_macro2::fallback::unforce();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_are_located_in_file() {
+        assert!(locate_errors("<div>\n  <p>\"ok\"</p>\n</div>").is_empty());
+
+        let errors = locate_errors("<div>\n  <p>\"a\"</p>\n  <img src=\"/a.png\" />\n</div>");
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].0, errors[0].1), (3, 3));
+        assert!(errors[0].2.contains("alt"));
+
+        let errors = locate_errors("<div>\n    <span></div>");
+        assert_eq!(errors[0].0, 2);
+        assert!(errors[0].2.contains("Mismatched closing tag"));
+    }
+}
@@ -1,17 +1,26 @@
 // Force rebuild 3
+#![cfg_attr(feature = "nightly", feature(proc_macro_diagnostic))]
 mod component;
 
 mod accessibility_validator;
 mod action;
+mod attribute_value_validator;
+mod az_on;
 mod css;
 mod css_validator;
+mod debug;
 mod head;
 mod html_structure_validator;
+mod i18n;
+mod include_html;
 mod live;
+mod markdown;
+mod namespace_validator;
 #[cfg(feature = "schema")]
 mod schema;
 mod style;
 mod test_spacing;
+mod theme;
 mod token_parser;
 
 use proc_macro::TokenStream;
@@ -31,14 +40,21 @@ pub fn derive_schema(input: TokenStream) -> TokenStream {
     schema::derive_schema(input)
 }
 
+/// Component with builder props. `#[azumi::component(classes)]` also generates
+/// a `Classes` enum of its scoped class names in the component's module
 #[proc_macro_attribute]
-pub fn component(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    component::expand_component(item)
+pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
+    component::expand_component(attr, item)
 }
 
+/// Server action
+///
+/// Generates an Axum POST handler at `/_azumi/action/{fn_name}`.
+/// Optional `guard = path` arguments wrap the route with async middleware
+/// functions (`async fn(Request, Next) -> impl IntoResponse`), run in order.
 #[proc_macro_attribute]
-pub fn action(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    action::expand_action(item)
+pub fn action(attr: TokenStream, item: TokenStream) -> TokenStream {
+    action::expand_action(attr, item)
 }
 
 /// Azumi Live - Compiler-driven optimistic UI
@@ -63,6 +79,8 @@ pub fn live(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// - `self.field -= value` → Sub prediction
 ///
 /// Generates Axum action handlers automatically.
+/// Guards can be applied to every method with `guard = path` in the
+/// attribute arguments, or to a single method with `#[guard(path)]`.
 #[proc_macro_attribute]
 pub fn live_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     live::expand_live_impl(attr, item)
@@ -76,6 +94,52 @@ impl Parse for NodesWrapper {
     }
 }
 
+/// Parses a class list: class={[("active", is_active), ("big", big)]}
+/// Returns None if the expression is not an array of ("literal", condition) pairs.
+fn parse_class_list(tokens: &proc_macro2::TokenStream) -> Option<Vec<syn::LitStr>> {
+    let syn::Expr::Array(array) = syn::parse2::<syn::Expr>(tokens.clone()).ok()? else {
+        return None;
+    };
+    array
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            syn::Expr::Tuple(tuple) if tuple.elems.len() == 2 => match &tuple.elems[0] {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(s),
+                    ..
+                }) => Some(s.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// A class list with each literal replaced by its scoped names:
+/// `[("active", on)]` -> `[("active-s1a2b", on)]`
+fn scope_class_list(
+    tokens: &proc_macro2::TokenStream,
+    ctx: &GenerationContext,
+) -> proc_macro2::TokenStream {
+    let Ok(syn::Expr::Array(mut array)) = syn::parse2::<syn::Expr>(tokens.clone()) else {
+        return tokens.clone();
+    };
+    for elem in array.elems.iter_mut() {
+        if let syn::Expr::Tuple(tuple) = elem {
+            if let Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(name),
+                ..
+            })) = tuple.elems.first()
+            {
+                let scoped = ctx.scoped_classes(&name.value());
+                tuple.elems[0] = syn::Expr::Verbatim(scoped);
+            }
+        }
+    }
+    quote! { #array }
+}
+
 /// Validates that a style attribute only contains CSS custom properties (--variables).
 /// Returns Ok(()) if valid, Err(error_message) if invalid.
 fn validate_style_only_css_vars(style_value: &str) -> Result<(), String> {
@@ -111,18 +175,143 @@ fn validate_style_only_css_vars(style_value: &str) -> Result<(), String> {
 #[proc_macro]
 pub fn html(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as token_parser::HtmlInput);
-    let nodes = input.nodes;
+    expand_html(input.nodes, Vec::new())
+}
+
+/// Compile a Markdown file into a component at build time
+/// Usage: markdown!("content/intro.md") - path is relative to Cargo.toml
+#[proc_macro]
+pub fn markdown(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as syn::LitStr);
+    match markdown::load_markdown_file(&path.value(), path.span()) {
+        Ok((nodes, full_path)) => {
+            let file_dep = quote! { const _: &str = include_str!(#full_path); };
+            expand_html(nodes, vec![file_dep])
+        }
+        Err(msg) => syn::Error::new(path.span(), msg).to_compile_error().into(),
+    }
+}
+
+/// Render an external template file with html! syntax
+/// Usage: include_html!("templates/page.azumi") - path is relative to Cargo.toml
+#[proc_macro]
+pub fn include_html(input: TokenStream) -> TokenStream {
+    include_html::expand_include_html(input)
+}
+
+/// Translated message checked against the locales/ catalogs at compile time
+/// Usage: {t!("greeting", name = user.name)}
+#[proc_macro]
+pub fn t(input: TokenStream) -> TokenStream {
+    i18n::expand_translate(input)
+}
+
+/// Design tokens from theme.toml as `:root` CSS variables, with dark variants
+/// Usage: html! { <head>{azumi::theme!()}</head> }
+#[proc_macro]
+pub fn theme(input: TokenStream) -> TokenStream {
+    theme::expand_theme(input)
+}
+
+/// A compiler warning at `span`. Stable proc macros can't emit warnings, so this
+/// reads a deprecated constant whose note is the message; `#[allow(deprecated)]`
+/// on the surrounding item silences it. With the `nightly` feature the warning
+/// goes through `proc_macro::Diagnostic` instead.
+fn compile_warning(span: proc_macro2::Span, msg: &str) -> proc_macro2::TokenStream {
+    #[cfg(feature = "nightly")]
+    if proc_macro::is_available() {
+        span.unwrap().warning(msg).emit();
+        return quote! {};
+    }
+
+    let name = quote::format_ident!("azumi_warning", span = span);
+    quote! {
+        {
+            #[deprecated(note = #msg)]
+            #[allow(non_upper_case_globals)]
+            const #name: () = ();
+            let _ = #name;
+        }
+    }
+}
+
+/// The lowered CSS and bundle id of a stylesheet, or None when it is empty
+fn lowered_stylesheet(css: &str, global: bool) -> Result<Option<(String, String)>, String> {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let css = crate::css::lower_css(css)?;
+    if css.trim().is_empty() {
+        return Ok(None);
+    }
+    let mut hasher = DefaultHasher::new();
+    css.hash(&mut hasher);
+    let id = format!("{}{:x}", if global { "g" } else { "s" }, hasher.finish());
+    Ok(Some((id, css)))
+}
+
+/// Register a stylesheet for the /azumi.css bundle (keyed by content hash, so
+/// identical CSS is stored once) and write it inline unless the bundle is served
+/// or a critical-CSS render is collecting it.
+/// The CSS is lowered for the crate's browser targets first (see css::lower_css).
+fn emit_stylesheet(css: &str, global: bool, inline_format: &str) -> proc_macro2::TokenStream {
+    let (id, css) = match lowered_stylesheet(css, global) {
+        Ok(Some(sheet)) => sheet,
+        Ok(None) => return quote! {},
+        Err(msg) => return quote! { compile_error!(#msg); },
+    };
+
+    quote! {
+        azumi::inventory::submit! {
+            azumi::styles::Stylesheet { id: #id, css: #css, global: #global }
+        }
+        if azumi::styles::should_inline(#id, #css) {
+            write!(f, #inline_format, #css)?;
+        }
+    }
+}
+
+/// Read a file relative to the calling crate's Cargo.toml.
+/// Returns the contents and the absolute path (for include_str! change tracking).
+fn read_manifest_file(path: &str) -> Result<(String, String), String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|e| e.to_string())?;
+    let full_path = std::path::Path::new(&manifest_dir).join(path);
+    let source = std::fs::read_to_string(&full_path).map_err(|e| {
+        format!(
+            "Cannot read '{}': {}. Paths are relative to Cargo.toml.",
+            full_path.display(),
+            e
+        )
+    })?;
+    Ok((source, full_path.to_string_lossy().to_string()))
+}
 
+fn expand_html(
+    nodes: Vec<token_parser::Node>,
+    file_deps: Vec<proc_macro2::TokenStream>,
+) -> TokenStream {
     // 1. Process styles (hoist <style> tags)
     let (style_bindings, scoped_css, global_css) = process_styles(&nodes);
 
-    // 2. CSS dependencies are no longer collected for external files
-    let css_deps: Vec<proc_macro2::TokenStream> = Vec::new();
+    let global_style = emit_stylesheet(&global_css, true, "<style>{}</style>");
+    let scoped_style = emit_stylesheet(
+        &scoped_css,
+        false,
+        "<style data-azumi-internal=\"true\">{}</style>",
+    );
 
-    // 3. Generate HTML string construction code
+    // Stylesheets shared through @export and composes:
+    let mut style_blocks = Vec::new();
+    style::scoped_style_blocks(&nodes, &mut style_blocks);
+    let shared_styles: proc_macro2::TokenStream = style_blocks
+        .into_iter()
+        .map(style::shared_stylesheets)
+        .collect();
+
+    // 2. Generate HTML string construction code
     let html_construction = generate_nodes(&nodes);
 
-    // 4. Generate bind validation checks
+    // 3. Generate bind validation checks
     let mut validation_checks = Vec::new();
     collect_bind_checks(&nodes, &mut validation_checks);
 
@@ -131,8 +320,8 @@ pub fn html(input: TokenStream) -> TokenStream {
             // Inject style bindings (hoisted)
             #style_bindings
 
-            // CSS dependency tracking (forces recompile when CSS changes)
-            #(#css_deps)*
+            // Source file dependency tracking (forces recompile when they change)
+            #(#file_deps)*
 
             // Validation block (compile-time only)
             const _: () = {
@@ -141,14 +330,10 @@ pub fn html(input: TokenStream) -> TokenStream {
 
             // Runtime HTML generation
             azumi::from_fn(move |f| {
-                // Inject global CSS first (unscoped)
-                if !#global_css.is_empty() {
-                    write!(f, "<style>{}</style>", #global_css)?;
-                }
-                // Inject scoped CSS
-                if !#scoped_css.is_empty() {
-                     write!(f, "<style data-azumi-internal=\"true\">{}</style>", #scoped_css)?;
-                }
+                // Inject global CSS first (unscoped), then scoped CSS
+                #global_style
+                #shared_styles
+                #scoped_style
                 #html_construction
             })
         }
@@ -163,10 +348,7 @@ fn extract_styles(nodes: Vec<token_parser::Node>) -> (Vec<token_parser::StyleBlo
 
     for node in nodes {
         match node {
-            token_parser::Node::Block(token_parser::Block::Style(style)) => {
-                eprintln!("DEBUG: Found style block. Global: {}", style.is_global);
-                styles.push(style);
-            }
+            token_parser::Node::Block(token_parser::Block::Style(style)) => styles.push(style),
             _ => other_nodes.push(node),
         }
     }
@@ -179,12 +361,9 @@ fn process_styles(nodes: &[token_parser::Node]) -> (proc_macro2::TokenStream, St
     let mut scoped_css = String::new();
     let mut global_css = String::new();
 
-    eprintln!("DEBUG: process_styles called with {} nodes", nodes.len());
-
     for node in nodes {
         match node {
             token_parser::Node::Block(token_parser::Block::Style(style_block)) => {
-                eprintln!("DEBUG: process_styles found style block");
                 if style_block.is_global {
                     // Global styles: validate but don't scope, but DO generate bindings
                     let output = style::process_global_style_macro(style_block.content.clone());
@@ -227,6 +406,12 @@ fn process_styles(nodes: &[token_parser::Node]) -> (proc_macro2::TokenStream, St
                     bindings.extend(b);
                     scoped_css.push_str(&s);
                     global_css.push_str(&g);
+                    if let Some(empty_branch) = &for_block.empty_branch {
+                        let (b, s, g) = process_styles(empty_branch);
+                        bindings.extend(b);
+                        scoped_css.push_str(&s);
+                        global_css.push_str(&g);
+                    }
                 }
                 token_parser::Block::Match(match_block) => {
                     for arm in &match_block.arms {
@@ -284,6 +469,9 @@ fn collect_bind_checks(nodes: &[token_parser::Node], checks: &mut Vec<proc_macro
                     }
                     token_parser::Block::For(for_block) => {
                         collect_bind_checks(&for_block.body, checks);
+                        if let Some(empty_branch) = &for_block.empty_branch {
+                            collect_bind_checks(empty_branch, checks);
+                        }
                     }
                     token_parser::Block::Match(match_block) => {
                         for arm in &match_block.arms {
@@ -408,37 +596,85 @@ fn strip_outer_quotes(s: &str) -> String {
     s.to_string()
 }
 
+/// Minify mode: collapse whitespace runs to a single space.
+/// Never trims completely, so "a " + <b> keeps its separating space.
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
+
 #[derive(Clone, PartialEq, Debug)]
 enum Context {
     Normal,
     Script,
+    Preformatted, // <pre> / <textarea>: whitespace is significant
 }
 
 #[derive(Clone, Debug)]
 struct GenerationContext {
     mode: Context,
-    scope_id: Option<String>,
+    // Classes of the template's scoped <style> blocks and the values their
+    // bindings hold, for class="..." words (`card` -> `"card-s1a2b"`)
+    classes: std::rc::Rc<std::collections::HashMap<String, proc_macro2::TokenStream>>,
 }
 
 impl GenerationContext {
-    fn normal() -> Self {
+    fn with_classes(classes: std::collections::HashMap<String, proc_macro2::TokenStream>) -> Self {
         Self {
             mode: Context::Normal,
-            scope_id: None,
+            classes: std::rc::Rc::new(classes),
         }
     }
 
-    fn with_scope(scope_id: String) -> Self {
+    fn with_mode(&self, mode: Context) -> Self {
         Self {
-            mode: Context::Normal,
-            scope_id: Some(scope_id),
+            mode,
+            classes: self.classes.clone(),
         }
     }
 
-    fn with_mode(&self, mode: Context) -> Self {
-        Self {
-            mode,
-            scope_id: self.scope_id.clone(),
+    // Scoped names for class words, as one `&'static str` expression:
+    // `card big` -> `"card-s1a2b big-s1a2b"`. Unknown words (already reported
+    // by validate_nodes) are kept as written.
+    fn scoped_classes(&self, words: &str) -> proc_macro2::TokenStream {
+        let parts: Vec<_> = words
+            .split_whitespace()
+            .map(|word| match self.classes.get(word) {
+                Some(value) => value.clone(),
+                None => quote! { #word },
+            })
+            .collect();
+        let literals: Option<Vec<String>> = parts
+            .iter()
+            .map(|part| syn::parse2::<syn::LitStr>(part.clone()).ok().map(|s| s.value()))
+            .collect();
+        match (parts.as_slice(), literals) {
+            ([part], _) => part.clone(),
+            (_, Some(literals)) => {
+                let joined = literals.join(" ");
+                quote! { #joined }
+            }
+            // `composes:` values are const blocks, joined at compile time too
+            _ => quote! {
+                {
+                    const PARTS: &[&str] = &[#(#parts),*];
+                    const BYTES: [u8; azumi::styles::joined_len(PARTS)] = azumi::styles::join_classes(PARTS);
+                    const CLASSES: &str = azumi::styles::class_str(&BYTES);
+                    CLASSES
+                }
+            },
         }
     }
 }
@@ -503,6 +739,9 @@ fn collect_styles_recursive(
                 }
                 token_parser::Block::For(for_block) => {
                     collect_styles_recursive(&for_block.body, global_css, scoped_css);
+                    if let Some(empty_branch) = &for_block.empty_branch {
+                        collect_styles_recursive(empty_branch, global_css, scoped_css);
+                    }
                 }
                 token_parser::Block::Match(match_block) => {
                     for arm in &match_block.arms {
@@ -529,7 +768,7 @@ fn generate_body(nodes: &[token_parser::Node]) -> proc_macro2::TokenStream {
     }
 
     // Collect all CSS content first (needed for validation and scoping)
-    let (global_css, scoped_css) = collect_all_styles(nodes);
+    let (_, scoped_css) = collect_all_styles(nodes);
 
     // Pass 0.5: Strict CSS Validation (New Feature)
     // Extract valid selectors from the SCOPED CSS only (global CSS is opt-out)
@@ -542,56 +781,33 @@ fn generate_body(nodes: &[token_parser::Node]) -> proc_macro2::TokenStream {
         return style_validation_errors;
     }
 
-    // Pass 1: Check if component has any style tags
-    let has_global = !global_css.is_empty();
-    let has_scoped = !scoped_css.is_empty();
-
-    if has_global || has_scoped {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        // Generate body
-        let body = if has_scoped {
-            // Generate scope ID from scoped CSS only
-            let mut hasher = DefaultHasher::new();
-            scoped_css.hash(&mut hasher);
-            let hash = hasher.finish();
-            let scope_id = format!("s{:x}", hash);
-
-            // Scope the CSS
-            let scoped_output = crate::css::scope_css(&scoped_css, &scope_id);
-
-            // Generate body with scope context
-            let ctx = GenerationContext::with_scope(scope_id.clone());
-            let body_content = generate_body_with_context(nodes, &ctx);
-
-            // Inject global CSS first (unscoped), then scoped CSS
-            if has_global {
-                quote! {
-                    write!(f, "<style>{}</style>", #global_css)?;
-                    write!(f, "<style>{}</style>", #scoped_output)?;
-                    #body_content
-                }
-            } else {
-                quote! {
-                    write!(f, "<style>{}</style>", #scoped_output)?;
-                    #body_content
-                }
-            }
-        } else {
-            // Only global CSS, no scoping needed
-            let ctx = GenerationContext::normal();
-            let body_content = generate_body_with_context(nodes, &ctx);
-
-            quote! {
-                write!(f, "<style>{}</style>", #global_css)?;
-                #body_content
-            }
-        };
+    // Pass 0.6: var(--x) and style={ --x: ... } against the theme.toml tokens
+    let theme_dependency = match theme::validate_css_variables(nodes) {
+        Ok(dependency) => dependency,
+        Err(errors) => return errors,
+    };
 
-        body
+    // The stylesheets themselves are emitted once, class-renamed, by expand_html.
+    // A later block's class shadows an earlier one's, like the bindings.
+    let mut style_blocks = Vec::new();
+    style::scoped_style_blocks(nodes, &mut style_blocks);
+    let classes = style_blocks
+        .into_iter()
+        .flat_map(style::scoped_classes)
+        .collect();
+    let body = generate_body_with_context(nodes, &GenerationContext::with_classes(classes));
+
+    // Selectors the template never refers to are warnings, not errors
+    let unused_selectors = if scoped_css.is_empty() {
+        quote! {}
     } else {
-        generate_body_with_context(nodes, &GenerationContext::normal())
+        css_validator::warn_unused_selectors(nodes)
+    };
+
+    quote! {
+        #theme_dependency
+        #unused_selectors
+        #body
     }
 }
 
@@ -604,6 +820,33 @@ fn validate_nodes(
     use quote::quote_spanned;
     let mut errors = vec![];
 
+    fn check_class_names(
+        val: &str,
+        error_span: proc_macro2::Span,
+        valid_classes: &std::collections::HashSet<String>,
+        has_scoped_css: bool,
+        errors: &mut Vec<proc_macro2::TokenStream>,
+    ) {
+        for class_name in val.split_whitespace() {
+            if !valid_classes.contains(class_name) {
+                let msg = if has_scoped_css {
+                    format!(
+                        "CSS class '{}' is not defined in any CSS file. Check for typos or add the class to your CSS.",
+                        class_name
+                    )
+                } else {
+                    format!(
+                        "CSS class '{}' is used but no CSS styles are defined for this component. Import a CSS file with <style src=\"...\" />.",
+                        class_name
+                    )
+                };
+                errors.push(quote_spanned! { error_span =>
+                    compile_error!(#msg);
+                });
+            }
+        }
+    }
+
     fn collect_errors_recursive(
         nodes: &[token_parser::Node],
         valid_classes: &std::collections::HashSet<String>,
@@ -646,27 +889,28 @@ fn validate_nodes(
                         if name == "class" {
                             match &attr.value {
                                 token_parser::AttributeValue::Static(val) => {
-                                    for class_name in val.split_whitespace() {
-                                        if !valid_classes.contains(class_name) {
-                                            let msg = if has_scoped_css {
-                                                format!(
-                                                    "CSS class '{}' is not defined in any CSS file. Check for typos or add the class to your CSS.",
-                                                    class_name
-                                                )
-                                            } else {
-                                                format!(
-                                                    "CSS class '{}' is used but no CSS styles are defined for this component. Import a CSS file with <style src=\"...\" />.",
-                                                    class_name
-                                                )
-                                            };
-                                            let error_span = attr.value_span.unwrap_or(attr.span);
-                                            errors.push(quote_spanned! { error_span =>
-                                                compile_error!(#msg);
-                                            });
-                                        }
-                                    }
+                                    let error_span = attr.value_span.unwrap_or(attr.span);
+                                    check_class_names(
+                                        val,
+                                        error_span,
+                                        valid_classes,
+                                        has_scoped_css,
+                                        errors,
+                                    );
                                 }
                                 token_parser::AttributeValue::Dynamic(tokens) => {
+                                    // Class list: every literal must be a known class
+                                    if let Some(names) = parse_class_list(tokens) {
+                                        for lit in names {
+                                            check_class_names(
+                                                &lit.value(),
+                                                lit.span(),
+                                                valid_classes,
+                                                has_scoped_css,
+                                                errors,
+                                            );
+                                        }
+                                    }
                                     // Check if variable name matches an ID but is used in class
                                     if let Ok(ident) = syn::parse2::<syn::Ident>(tokens.clone()) {
                                         let var_name = ident.to_string();
@@ -735,8 +979,13 @@ fn validate_nodes(
                             }
                         }
 
-                        // Rule 4: Validate attribute name (Strict HTML)
-                        if let Some(err) = html_structure_validator::validate_attribute_name(attr) {
+                        // Rule 4: Validate attribute name (Strict HTML, or SVG/MathML allow-list)
+                        let name_error = if elem.namespace == token_parser::Namespace::Html {
+                            html_structure_validator::validate_attribute_name(attr)
+                        } else {
+                            namespace_validator::validate_foreign_attribute(elem.namespace, attr)
+                        };
+                        if let Some(err) = name_error {
                             errors.push(err);
                         }
 
@@ -746,42 +995,64 @@ fn validate_nodes(
                         if name == "az-on" {
                             if let token_parser::AttributeValue::Dynamic(tokens) = &attr.value {
                                 let dsl = tokens.to_string();
-                                // Parse "-> #target"
+                                // Parse "-> #target [swap] [transition]"
                                 if let Some(idx) = dsl.find("->") {
-                                    let rest = &dsl[idx + 2..];
-                                    // TokenStream::to_string() adds spaces around punctuation (e.g. "# target")
-                                    // We need to strip them to get the ID
-                                    let target = rest.replace(" ", "");
-                                    if target.starts_with('#') {
-                                        let id_name = &target[1..];
-                                        // Only validate if we have valid_ids to check against
-                                        // If the target ID is not in our list, it might be:
-                                        // 1. A dynamic ID (id={expr}) - can't validate
-                                        // 2. An ID in a different component - can't validate
-                                        // 3. A typo - we want to catch this
-                                        //
-                                        // We only error if we have CSS (meaning we collected IDs)
-                                        // AND the ID is not found. If there's no CSS, we can't
-                                        // know if an ID is valid or not.
-                                        if !valid_ids.is_empty() && !valid_ids.contains(id_name) {
-                                            // Only emit error if this looks like a typo
-                                            // Skip validation for IDs that might be dynamic
-                                            let msg = format!(
-                                                "az-on target ID '{}' not found in CSS. If using a dynamic id={{expr}}, this validation cannot run. Otherwise, define #{} in your CSS.",
-                                                id_name, id_name
-                                            );
-                                            // Use value_span to point to the attribute value
-                                            let error_span = attr.value_span.unwrap_or(attr.span);
+                                    let error_span = attr.value_span.unwrap_or(attr.span);
+                                    match az_on::parse_target(&dsl[idx + 2..]) {
+                                        Err(msg) => {
                                             errors.push(quote_spanned! { error_span =>
                                                 compile_error!(#msg);
                                             });
                                         }
+                                        Ok(spec) => {
+                                            if let Some(id_name) = spec.target.strip_prefix('#') {
+                                                // Only validate if we have valid_ids to check against
+                                                // If the target ID is not in our list, it might be:
+                                                // 1. A dynamic ID (id={expr}) - can't validate
+                                                // 2. An ID in a different component - can't validate
+                                                // 3. A typo - we want to catch this
+                                                //
+                                                // We only error if we have CSS (meaning we collected IDs)
+                                                // AND the ID is not found. If there's no CSS, we can't
+                                                // know if an ID is valid or not.
+                                                if !valid_ids.is_empty() && !valid_ids.contains(id_name) {
+                                                    let msg = format!(
+                                                        "az-on target ID '{}' not found in CSS. If using a dynamic id={{expr}}, this validation cannot run. Otherwise, define #{} in your CSS.",
+                                                        id_name, id_name
+                                                    );
+                                                    errors.push(quote_spanned! { error_span =>
+                                                        compile_error!(#msg);
+                                                    });
+                                                }
+                                            }
+                                        }
                                     }
                                 }
                             }
                         }
                     }
 
+                    // Rule 6: az-oob fragments are matched by id on the client
+                    if let Some(oob_attr) = elem.attrs.iter().find(|a| a.name == "az-oob") {
+                        if !elem.attrs.iter().any(|a| a.name == "id") {
+                            errors.push(quote_spanned! { oob_attr.span =>
+                                compile_error!("Elements marked az-oob need an id so the client can find the element to swap. Add id={my_id}.");
+                            });
+                        }
+                        // Optional value picks the swap mode: az-oob="beforeend"
+                        if let token_parser::AttributeValue::Static(mode) = &oob_attr.value {
+                            if let Err(msg) = az_on::validate_swap(mode) {
+                                let error_span = oob_attr.value_span.unwrap_or(oob_attr.span);
+                                errors.push(quote_spanned! { error_span =>
+                                    compile_error!(#msg);
+                                });
+                            }
+                        }
+                    }
+
+                    // Attribute value validation (enumerated and numeric attributes)
+                    errors.extend(attribute_value_validator::validate_attribute_values(elem));
+
                     // Accessibility validation
                     if let Some(err) = accessibility_validator::validate_img_alt(elem) {
                         errors.push(err);
@@ -803,7 +1074,12 @@ fn validate_nodes(
                     }
 
                     // HTML Structure validation
-                    if let Some(err) = html_structure_validator::validate_tag_name(elem) {
+                    let tag_error = if elem.namespace == token_parser::Namespace::Html {
+                        html_structure_validator::validate_tag_name(elem)
+                    } else {
+                        namespace_validator::validate_foreign_tag(elem)
+                    };
+                    if let Some(err) = tag_error {
                         errors.push(err);
                     }
                     errors.extend(html_structure_validator::validate_table_children(elem));
@@ -888,6 +1164,18 @@ fn validate_nodes(
                             is_inside_button,
                             is_inside_anchor,
                         );
+                        if let Some(empty_branch) = &for_block.empty_branch {
+                            collect_errors_recursive(
+                                empty_branch,
+                                valid_classes,
+                                valid_ids,
+                                has_scoped_css,
+                                errors,
+                                is_inside_form,
+                                is_inside_button,
+                                is_inside_anchor,
+                            );
+                        }
                     }
                     token_parser::Block::Match(match_block) => {
                         for arm in &match_block.arms {
@@ -958,6 +1246,8 @@ fn generate_body_with_context(
                 // Determine context for children
                 let child_context = if name == "script" {
                     ctx.with_mode(Context::Script)
+                } else if name == "pre" || name == "textarea" {
+                    ctx.with_mode(Context::Preformatted)
                 } else {
                     ctx.clone()
                 };
@@ -1122,15 +1412,43 @@ fn generate_body_with_context(
                     }
 
                     match &attr.value {
+                        token_parser::AttributeValue::Static(val)
+                            if attr_name == "class" && !ctx.classes.is_empty() =>
+                        {
+                            // class="card big" -> class="card-s1a2b big-s1a2b"
+                            let classes = ctx.scoped_classes(val);
+                            attr_code.extend(quote! {
+                                write!(f, " class=\"{}\"", #classes)?;
+                            });
+                        }
                         token_parser::AttributeValue::Static(val) => {
                             attr_code.extend(quote! {
                                 write!(f, " {}=\"{}\"", #attr_name, azumi::Escaped(#val))?;
                             });
                         }
                         token_parser::AttributeValue::Dynamic(expr) => {
-                            attr_code.extend(quote! {
-                                write!(f, " {}=\"{}\"", #attr_name, azumi::Escaped(&(#expr)))?;
-                            });
+                            if attr_name == "class" && parse_class_list(expr).is_some() {
+                                // class={[("active", is_active), ...]}, each literal scoped
+                                let expr = scope_class_list(expr, ctx);
+                                attr_code.extend(quote! {
+                                    write!(f, " class=\"{}\"", azumi::Escaped(azumi::attrs::class_list(&#expr)))?;
+                                });
+                            } else if token_parser::BOOLEAN_ATTRS.contains(&attr_name.as_str()) {
+                                // disabled={bool} / disabled={Option<bool>}: written only when true
+                                attr_code.extend(quote! {
+                                    if azumi::attrs::BoolAttr::is_on(&(#expr)) {
+                                        write!(f, " {}", #attr_name)?;
+                                    }
+                                });
+                            } else {
+                                // Option values are omitted when None
+                                attr_code.extend(quote! {
+                                    {
+                                        use azumi::attrs::FallbackAttr; // Import trait for fallback
+                                        azumi::attrs::AttrWrapper(&(#expr)).write_attr(f, #attr_name)?;
+                                    }
+                                });
+                            }
                         }
                         token_parser::AttributeValue::StyleDsl(props) => {
                             // Style DSL is only valid for 'style' attribute, which is handled above.
@@ -1149,6 +1467,32 @@ fn generate_body_with_context(
                     }
                 }
 
+                // Spread attributes: {..attrs}
+                for spread in &elem.spreads {
+                    attr_code.extend(quote! {
+                        azumi::Attributes::write_to(&(#spread), f)?;
+                    });
+                }
+
+                // <html> gets lang/dir from the negotiated locale unless given explicitly.
+                // An explicit static lang="..." decides dir itself; with lang={expr}
+                // dir is left to the template.
+                if name == "html" {
+                    let lang = elem.attrs.iter().find(|a| a.name == "lang");
+                    let dir = !elem.attrs.iter().any(|a| a.name == "dir");
+                    match lang.map(|a| &a.value) {
+                        None => attr_code.extend(quote! {
+                            azumi::i18n::write_lang_attrs(f, true, #dir)?;
+                        }),
+                        Some(token_parser::AttributeValue::Static(lang)) if dir => {
+                            attr_code.extend(quote! {
+                                azumi::i18n::write_dir_for(f, #lang)?;
+                            })
+                        }
+                        Some(_) => {}
+                    }
+                }
+
                 // Auto-optimization for <img> tags
                 if name == "img" {
                     // Check if attributes were already present in the source
@@ -1167,24 +1511,12 @@ fn generate_body_with_context(
                     }
                 }
 
-                // Generate element with potential scope attribute from context
-                if let Some(ref scope_id) = ctx.scope_id {
-                    quote! {
-                        write!(f, "<{}", #name)?;
-                        write!(f, " data-{}", #scope_id)?;
-                        #attr_code
-                        write!(f, ">")?;
-                        #children_code
-                        write!(f, "</{}>", #name)?;
-                    }
-                } else {
-                    quote! {
-                        write!(f, "<{}", #name)?;
-                        #attr_code
-                        write!(f, ">")?;
-                        #children_code
-                        write!(f, "</{}>", #name)?;
-                    }
+                quote! {
+                    write!(f, "<{}", #name)?;
+                    #attr_code
+                    write!(f, ">")?;
+                    #children_code
+                    write!(f, "</{}>", #name)?;
                 }
             }
             token_parser::Node::Text(text) => {
@@ -1194,6 +1526,11 @@ fn generate_body_with_context(
                 } else {
                     // Strip outer quotes from string literals for cleaner rendering
                     let stripped = strip_outer_quotes(content);
+                    let stripped = if cfg!(feature = "minify") && ctx.mode == Context::Normal {
+                        collapse_whitespace(&stripped)
+                    } else {
+                        stripped
+                    };
                     quote! { write!(f, "{}", #stripped)?; }
                 }
             }
@@ -1204,7 +1541,7 @@ fn generate_body_with_context(
                         // In JSON script tags, output raw (user provides JSON string via serde_json::to_string etc.)
                         quote! { write!(f, "{}", #content)?; }
                     }
-                    Context::Normal => {
+                    Context::Normal | Context::Preformatted => {
                         // In normal HTML, use Smart Interpolation
                         // RenderWrapper::render_azumi will pick:
                         // 1. Component::render (if it's a Component)
@@ -1226,7 +1563,26 @@ fn generate_body_with_context(
                 let content = &doctype.content;
                 quote! { write!(f, "<!DOCTYPE {}>", #content)?; }
             }
-            token_parser::Node::Fragment(frag) => generate_body_with_context(&frag.children, ctx),
+            token_parser::Node::Fragment(frag) => {
+                let children_code = generate_body_with_context(&frag.children, ctx);
+                if let Some(name) = &frag.name {
+                    // Markers are only written while azumi::render_fragment targets this name
+                    quote! {
+                        {
+                            let __azumi_fragment = azumi::fragment::is_target(#name);
+                            if __azumi_fragment {
+                                write!(f, "{}", azumi::fragment::START)?;
+                            }
+                            #children_code
+                            if __azumi_fragment {
+                                write!(f, "{}", azumi::fragment::END)?;
+                            }
+                        }
+                    }
+                } else {
+                    children_code
+                }
+            }
             token_parser::Node::Block(block) => match block {
                 token_parser::Block::If(if_block) => {
                     let condition = &if_block.condition;
@@ -1244,12 +1600,54 @@ fn generate_body_with_context(
                     }
                 }
                 token_parser::Block::For(for_block) => {
-                    let pattern = &for_block.pattern;
-                    let iterator = &for_block.iterator;
                     let body_code = generate_body_with_context(&for_block.body, ctx);
-                    quote! {
-                        for #pattern in #iterator {
-                            #body_code
+                    let (pattern, iterator, body_code) = if for_block.loop_context {
+                        // Collect first so loop.len / loop.last are known up front
+                        let item = &for_block.pattern;
+                        let iter = &for_block.iterator;
+                        let loop_var = quote::format_ident!("{}", token_parser::LOOP_VAR);
+                        (
+                            quote! { ((__azumi_index, __azumi_len), #item) },
+                            quote! {
+                                {
+                                    let __azumi_items: ::std::vec::Vec<_> =
+                                        ::std::iter::IntoIterator::into_iter(#iter).collect();
+                                    let __azumi_len = __azumi_items.len();
+                                    __azumi_items
+                                        .into_iter()
+                                        .enumerate()
+                                        .map(move |(index, item)| ((index, __azumi_len), item))
+                                }
+                            },
+                            quote! {
+                                let #loop_var = azumi::LoopContext::new(__azumi_index, __azumi_len);
+                                #body_code
+                            },
+                        )
+                    } else {
+                        (
+                            for_block.pattern.clone(),
+                            for_block.iterator.clone(),
+                            body_code,
+                        )
+                    };
+                    if let Some(empty_branch) = &for_block.empty_branch {
+                        let empty_code = generate_body_with_context(empty_branch, ctx);
+                        quote! {
+                            let mut __azumi_for_empty = true;
+                            for #pattern in #iterator {
+                                __azumi_for_empty = false;
+                                #body_code
+                            }
+                            if __azumi_for_empty {
+                                #empty_code
+                            }
+                        }
+                    } else {
+                        quote! {
+                            for #pattern in #iterator {
+                                #body_code
+                            }
                         }
                     }
                 }
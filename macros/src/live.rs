@@ -259,10 +259,22 @@ pub fn expand_live_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Parse attributes to find component="name"
     let args = parse_macro_input!(attr with syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated);
     let mut component_name = None;
+    let mut impl_guards = Vec::new();
 
     for arg in args {
         if let syn::Meta::NameValue(nv) = arg {
-            if nv.path.is_ident("component") {
+            if nv.path.is_ident("guard") {
+                if let syn::Expr::Path(expr_path) = nv.value {
+                    impl_guards.push(expr_path.path);
+                } else {
+                    return syn::Error::new_spanned(
+                        nv.value,
+                        "guard must be a path to an async middleware function",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            } else if nv.path.is_ident("component") {
                 if let syn::Expr::Lit(syn::ExprLit {
                     lit: syn::Lit::Str(lit),
                     ..
@@ -303,7 +315,26 @@ pub fn expand_live_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
             let handler_name = format_ident!("{}_handler", method_name);
             let router_name = format_ident!("{}_router", method_name);
 
-            // Keep original method
+            // Impl-level guards run first, then per-method #[guard(path)]
+            let mut guards = impl_guards.clone();
+            let mut method = method.clone();
+            let mut guard_error = None;
+            method.attrs.retain(|attr| {
+                if !attr.path().is_ident("guard") {
+                    return true;
+                }
+                match attr.parse_args::<syn::Path>() {
+                    Ok(path) => guards.push(path),
+                    Err(err) => guard_error = Some(err),
+                }
+                false
+            });
+            if let Some(err) = guard_error {
+                return err.to_compile_error().into();
+            }
+            let guard_layers = crate::action::guard_layers(&guards);
+
+            // Keep original method (without #[guard] attributes)
             original_methods.push(quote! { #method });
 
             // Generate Axum handler
@@ -311,6 +342,7 @@ pub fn expand_live_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                 let comp_mod = format_ident!("{}_component", comp_name);
                 quote! {
                     pub async fn #handler_name(
+                        _csrf: azumi::csrf::CsrfGuard,
                         axum::extract::Json(mut state): axum::extract::Json<#struct_name>
                     ) -> impl axum::response::IntoResponse {
                         state.#method_name();
@@ -329,11 +361,13 @@ pub fn expand_live_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                     #[allow(non_snake_case)]
                     pub fn #router_name() -> axum::routing::MethodRouter<()> {
                         axum::routing::post(#handler_name)
+                            #(#guard_layers)*
                     }
                 }
             } else {
                 quote! {
                     pub async fn #handler_name(
+                        _csrf: azumi::csrf::CsrfGuard,
                         axum::extract::Json(mut state): axum::extract::Json<#struct_name>
                     ) -> impl axum::response::IntoResponse {
                         state.#method_name();
@@ -343,6 +377,7 @@ pub fn expand_live_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                     #[allow(non_snake_case)]
                     pub fn #router_name() -> axum::routing::MethodRouter<()> {
                         axum::routing::post(#handler_name)
+                            #(#guard_layers)*
                     }
                 }
             };
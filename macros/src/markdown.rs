@@ -0,0 +1,314 @@
+//! Markdown compiled to html! nodes at macro expansion time.
+//! The generated elements go through the same structure, accessibility and
+//! CSS validators as hand-written markup.
+
+use crate::token_parser::{Attribute, AttributeValue, Element, Namespace, Node, Text};
+use proc_macro2::Span;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+
+/// Convert Markdown source to nodes. Raw HTML is rejected so every tag is validated.
+pub fn markdown_to_nodes(source: &str, span: Span) -> Result<Vec<Node>, String> {
+    let source = dedent(source);
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+
+    let mut builder = Builder {
+        root: Vec::new(),
+        stack: Vec::new(),
+        image: None,
+        span,
+    };
+    for event in Parser::new_ext(&source, options) {
+        builder.event(event)?;
+    }
+    Ok(builder.root)
+}
+
+/// Read a Markdown file relative to the calling crate's Cargo.toml.
+/// Returns the nodes and the absolute path (for include_str! change tracking).
+pub fn load_markdown_file(path: &str, span: Span) -> Result<(Vec<Node>, String), String> {
+    let (source, full_path) = crate::read_manifest_file(path)?;
+    let nodes = markdown_to_nodes(&source, span)?;
+    Ok((nodes, full_path))
+}
+
+struct Builder {
+    root: Vec<Node>,
+    stack: Vec<Element>,
+    image: Option<(Element, String)>, // <img> being built, and its alt text
+    span: Span,
+}
+
+impl Builder {
+    fn event(&mut self, event: Event) -> Result<(), String> {
+        match event {
+            Event::Start(tag) => self.start(tag)?,
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => match &mut self.image {
+                Some((_, alt)) => alt.push_str(&text),
+                None => self.push(self.text(&text)),
+            },
+            Event::Code(code) => {
+                let mut elem = self.element("code", vec![]);
+                elem.children.push(self.text(&code));
+                self.push(Node::Element(elem));
+            }
+            Event::SoftBreak => match &mut self.image {
+                Some((_, alt)) => alt.push(' '),
+                None => self.push(self.text("\n")),
+            },
+            Event::HardBreak => self.push(Node::Element(self.element("br", vec![]))),
+            Event::Rule => self.push(Node::Element(self.element("hr", vec![]))),
+            Event::Html(html) | Event::InlineHtml(html) => {
+                return Err(format!(
+                    "Raw HTML is not allowed in Markdown: '{}'. Put html! markup between Markdown strings instead.",
+                    html.trim()
+                ));
+            }
+            other => return Err(format!("Unsupported Markdown construct: {:?}", other)),
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, tag: Tag) -> Result<(), String> {
+        match tag {
+            Tag::Paragraph => self.open("p", vec![]),
+            Tag::Heading { level, .. } => self.open(&level.to_string(), vec![]),
+            Tag::BlockQuote(_) => self.open("blockquote", vec![]),
+            Tag::CodeBlock(kind) => {
+                self.open("pre", vec![]);
+                let lang = match &kind {
+                    CodeBlockKind::Fenced(info) => info.split_whitespace().next(),
+                    CodeBlockKind::Indented => None,
+                };
+                // data-lang rather than class="language-x", which would need a CSS rule
+                let attrs = lang.map(|l| vec![("data-lang", l)]).unwrap_or_default();
+                self.open("code", attrs);
+            }
+            Tag::List(Some(1)) => self.open("ol", vec![]),
+            Tag::List(Some(start)) => self.open("ol", vec![("start", &start.to_string())]),
+            Tag::List(None) => self.open("ul", vec![]),
+            Tag::Item => self.open("li", vec![]),
+            Tag::Table(_) => self.open("table", vec![]),
+            Tag::TableHead => {
+                self.open("thead", vec![]);
+                self.open("tr", vec![]);
+            }
+            Tag::TableRow => self.open("tr", vec![]),
+            Tag::TableCell => {
+                let in_head = self.stack.iter().any(|e| e.name == "thead");
+                self.open(if in_head { "th" } else { "td" }, vec![]);
+            }
+            Tag::Emphasis => self.open("em", vec![]),
+            Tag::Strong => self.open("strong", vec![]),
+            Tag::Strikethrough => self.open("del", vec![]),
+            Tag::Link {
+                dest_url, title, ..
+            } => {
+                let mut attrs = vec![("href", dest_url.as_ref())];
+                if !title.is_empty() {
+                    attrs.push(("title", title.as_ref()));
+                }
+                self.open("a", attrs);
+            }
+            Tag::Image {
+                dest_url, title, ..
+            } => {
+                let mut attrs = vec![("src", dest_url.as_ref())];
+                if !title.is_empty() {
+                    attrs.push(("title", title.as_ref()));
+                }
+                self.image = Some((self.element("img", attrs), String::new()));
+            }
+            Tag::HtmlBlock => {} // Rejected by the Html event it contains
+            other => return Err(format!("Unsupported Markdown construct: {:?}", other)),
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::CodeBlock => {
+                self.close(); // </code>
+                self.close(); // </pre>
+            }
+            TagEnd::TableHead => {
+                self.close(); // </tr>
+                self.close(); // </thead>
+                self.open("tbody", vec![]);
+            }
+            TagEnd::Table => {
+                self.close(); // </tbody>
+                self.close(); // </table>
+            }
+            TagEnd::Image => {
+                if let Some((mut img, alt)) = self.image.take() {
+                    // Markdown always provides alt text; ![](x.png) gives a decorative alt=""
+                    img.attrs.push(self.attribute("alt", &alt));
+                    self.push(Node::Element(img));
+                }
+            }
+            TagEnd::HtmlBlock => {}
+            _ => self.close(),
+        }
+    }
+
+    fn open(&mut self, name: &str, attrs: Vec<(&str, &str)>) {
+        let elem = self.element(name, attrs);
+        self.stack.push(elem);
+    }
+
+    fn close(&mut self) {
+        if let Some(elem) = self.stack.pop() {
+            self.push(Node::Element(elem));
+        }
+    }
+
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.root.push(node),
+        }
+    }
+
+    fn element(&self, name: &str, attrs: Vec<(&str, &str)>) -> Element {
+        Element {
+            name: name.to_string(),
+            namespace: Namespace::Html,
+            attrs: attrs
+                .into_iter()
+                .map(|(name, value)| self.attribute(name, value))
+                .collect(),
+            children: Vec::new(),
+            bind_struct: None,
+            spreads: Vec::new(),
+            span: self.span,
+        }
+    }
+
+    fn attribute(&self, name: &str, value: &str) -> Attribute {
+        Attribute {
+            name: name.to_string(),
+            name_span: self.span,
+            value: AttributeValue::Static(value.to_string()),
+            span: self.span,
+            value_span: Some(self.span),
+        }
+    }
+
+    // Text literals are written verbatim, so Markdown text is escaped here
+    fn text(&self, content: &str) -> Node {
+        Node::Text(Text {
+            content: escape_html(content),
+            span: self.span,
+        })
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Strip the indentation shared by all lines, so Markdown inside an
+/// indented string literal isn't read as a code block.
+fn dedent(source: &str) -> String {
+    let indent = source
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    source
+        .lines()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(nodes: &[Node]) -> Vec<String> {
+        nodes
+            .iter()
+            .filter_map(|n| match n {
+                Node::Element(e) => Some(e.name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dedent() {
+        assert_eq!(
+            dedent("\n    # Title\n\n    Text\n        code"),
+            "\n# Title\n\nText\n    code"
+        );
+    }
+
+    #[test]
+    fn test_block_structure() {
+        let nodes = markdown_to_nodes(
+            "# Title\n\nSome *text* & `Vec<T>`\n\n- a\n- b\n\n```rust\nfn main() {}\n```",
+            Span::call_site(),
+        )
+        .unwrap();
+        assert_eq!(names(&nodes), ["h1", "p", "ul", "pre"]);
+
+        let Node::Element(p) = &nodes[1] else {
+            unreachable!()
+        };
+        assert_eq!(names(&p.children), ["em", "code"]);
+        let Node::Text(amp) = &p.children[2] else {
+            panic!("Expected text");
+        };
+        assert_eq!(amp.content, " &amp; ");
+
+        let Node::Element(pre) = &nodes[3] else {
+            unreachable!()
+        };
+        let Node::Element(code) = &pre.children[0] else {
+            panic!("Expected <code>");
+        };
+        assert_eq!(code.attrs[0].name, "data-lang");
+    }
+
+    #[test]
+    fn test_tables_and_images() {
+        let nodes = markdown_to_nodes(
+            "| a | b |\n|---|---|\n| 1 | 2 |\n\n![Logo](/logo.png)",
+            Span::call_site(),
+        )
+        .unwrap();
+        let Node::Element(table) = &nodes[0] else {
+            panic!("Expected <table>");
+        };
+        assert_eq!(names(&table.children), ["thead", "tbody"]);
+
+        let Node::Element(p) = &nodes[1] else {
+            panic!("Expected <p>");
+        };
+        let Node::Element(img) = &p.children[0] else {
+            panic!("Expected <img>");
+        };
+        let alt = img.attrs.iter().find(|a| a.name == "alt").unwrap();
+        assert!(matches!(&alt.value, AttributeValue::Static(v) if v == "Logo"));
+    }
+
+    #[test]
+    fn test_raw_html_rejected() {
+        let err = markdown_to_nodes("Hello <b>world</b>", Span::call_site()).unwrap_err();
+        assert!(err.contains("Raw HTML is not allowed"));
+    }
+}
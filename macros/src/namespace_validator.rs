@@ -0,0 +1,397 @@
+//! Tag and attribute validation inside inline <svg> and <math> subtrees.
+//! Unlike HTML, names in these namespaces are case-sensitive (viewBox, clipPath).
+
+use crate::token_parser::{Attribute, Element, Namespace};
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+
+const SVG_ELEMENTS: &[&str] = &[
+    "a",
+    "animate",
+    "animateMotion",
+    "animateTransform",
+    "circle",
+    "clipPath",
+    "defs",
+    "desc",
+    "ellipse",
+    "feBlend",
+    "feColorMatrix",
+    "feComponentTransfer",
+    "feComposite",
+    "feConvolveMatrix",
+    "feDiffuseLighting",
+    "feDisplacementMap",
+    "feDistantLight",
+    "feDropShadow",
+    "feFlood",
+    "feFuncA",
+    "feFuncB",
+    "feFuncG",
+    "feFuncR",
+    "feGaussianBlur",
+    "feImage",
+    "feMerge",
+    "feMergeNode",
+    "feMorphology",
+    "feOffset",
+    "fePointLight",
+    "feSpecularLighting",
+    "feSpotLight",
+    "feTile",
+    "feTurbulence",
+    "filter",
+    "foreignObject",
+    "g",
+    "image",
+    "line",
+    "linearGradient",
+    "marker",
+    "mask",
+    "metadata",
+    "mpath",
+    "path",
+    "pattern",
+    "polygon",
+    "polyline",
+    "radialGradient",
+    "rect",
+    "script",
+    "set",
+    "stop",
+    "style",
+    "svg",
+    "switch",
+    "symbol",
+    "text",
+    "textPath",
+    "title",
+    "tspan",
+    "use",
+    "view",
+];
+
+const SVG_ATTRIBUTES: &[&str] = &[
+    // Core
+    "id",
+    "class",
+    "style",
+    "lang",
+    "tabindex",
+    "role",
+    "focusable",
+    "xmlns",
+    "href",
+    "target",
+    "download",
+    "rel",
+    "crossorigin",
+    "decoding",
+    "requiredExtensions",
+    "requiredFeatures",
+    "systemLanguage",
+    "version",
+    "baseProfile",
+    "zoomAndPan",
+    "contentScriptType",
+    "contentStyleType",
+    "externalResourcesRequired",
+    "autofocus",
+    "nonce",
+    "media",
+    "title",
+    "hreflang",
+    "ping",
+    "referrerpolicy",
+    "viewTarget",
+    // Geometry
+    "viewBox",
+    "preserveAspectRatio",
+    "width",
+    "height",
+    "x",
+    "y",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "r",
+    "rx",
+    "ry",
+    "d",
+    "points",
+    "pathLength",
+    "transform",
+    // Presentation
+    "fill",
+    "stroke",
+    "opacity",
+    "mask",
+    "filter",
+    "color",
+    "display",
+    "visibility",
+    "overflow",
+    "cursor",
+    "orient",
+    "clip",
+    "direction",
+    "kerning",
+    // Text
+    "dx",
+    "dy",
+    "rotate",
+    "lengthAdjust",
+    "textLength",
+    "startOffset",
+    "method",
+    "spacing",
+    "side",
+    // Gradients, patterns, clipping, markers
+    "offset",
+    "gradientUnits",
+    "gradientTransform",
+    "spreadMethod",
+    "fx",
+    "fy",
+    "fr",
+    "patternUnits",
+    "patternContentUnits",
+    "patternTransform",
+    "maskUnits",
+    "maskContentUnits",
+    "clipPathUnits",
+    "markerWidth",
+    "markerHeight",
+    "markerUnits",
+    "refX",
+    "refY",
+    // Filters
+    "filterUnits",
+    "primitiveUnits",
+    "in",
+    "in2",
+    "result",
+    "stdDeviation",
+    "mode",
+    "operator",
+    "k1",
+    "k2",
+    "k3",
+    "k4",
+    "type",
+    "values",
+    "tableValues",
+    "slope",
+    "intercept",
+    "amplitude",
+    "exponent",
+    "baseFrequency",
+    "numOctaves",
+    "seed",
+    "stitchTiles",
+    "scale",
+    "xChannelSelector",
+    "yChannelSelector",
+    "surfaceScale",
+    "diffuseConstant",
+    "specularConstant",
+    "specularExponent",
+    "kernelMatrix",
+    "order",
+    "divisor",
+    "bias",
+    "targetX",
+    "targetY",
+    "edgeMode",
+    "preserveAlpha",
+    "azimuth",
+    "elevation",
+    "pointsAtX",
+    "pointsAtY",
+    "pointsAtZ",
+    "z",
+    "kernelUnitLength",
+    "filterRes",
+    "limitingConeAngle",
+    "radius",
+    // Animation
+    "dur",
+    "begin",
+    "end",
+    "repeatCount",
+    "repeatDur",
+    "from",
+    "to",
+    "by",
+    "attributeName",
+    "attributeType",
+    "min",
+    "max",
+    "origin",
+    "calcMode",
+    "keyTimes",
+    "keySplines",
+    "keyPoints",
+    "additive",
+    "accumulate",
+    "restart",
+    "path",
+];
+
+const MATHML_ELEMENTS: &[&str] = &[
+    "math",
+    "maction",
+    "annotation",
+    "annotation-xml",
+    "menclose",
+    "merror",
+    "mfrac",
+    "mi",
+    "mmultiscripts",
+    "mn",
+    "mo",
+    "mover",
+    "mpadded",
+    "mphantom",
+    "mprescripts",
+    "mroot",
+    "mrow",
+    "ms",
+    "mspace",
+    "msqrt",
+    "mstyle",
+    "msub",
+    "msubsup",
+    "msup",
+    "mtable",
+    "mtd",
+    "mtext",
+    "mtr",
+    "munder",
+    "munderover",
+    "none",
+    "semantics",
+];
+
+const MATHML_ATTRIBUTES: &[&str] = &[
+    "id",
+    "class",
+    "style",
+    "dir",
+    "href",
+    "tabindex",
+    "xmlns",
+    "display",
+    "displaystyle",
+    "alttext",
+    "intent",
+    "arg",
+    "mathvariant",
+    "mathcolor",
+    "mathbackground",
+    "mathsize",
+    "scriptlevel",
+    "accent",
+    "accentunder",
+    "fence",
+    "form",
+    "largeop",
+    "lspace",
+    "rspace",
+    "maxsize",
+    "minsize",
+    "movablelimits",
+    "separator",
+    "stretchy",
+    "symmetric",
+    "linethickness",
+    "notation",
+    "columnalign",
+    "rowalign",
+    "columnspan",
+    "rowspan",
+    "columnlines",
+    "rowlines",
+    "frame",
+    "width",
+    "height",
+    "depth",
+    "voffset",
+    "encoding",
+    "actiontype",
+    "selection",
+];
+
+/// Rule 1: Elements inside <svg>/<math> must belong to that namespace
+pub fn validate_foreign_tag(elem: &Element) -> Option<TokenStream> {
+    let (allowed, label) = match elem.namespace {
+        Namespace::Html => return None,
+        Namespace::Svg => (SVG_ELEMENTS, "SVG"),
+        Namespace::MathMl => (MATHML_ELEMENTS, "MathML"),
+    };
+
+    if elem.name.contains('-') && elem.namespace == Namespace::Svg {
+        return None; // Custom elements are allowed anywhere
+    }
+    if allowed.contains(&elem.name.as_str()) {
+        return None;
+    }
+
+    let msg = match case_insensitive_match(&elem.name, allowed) {
+        Some(correct) => format!(
+            "Unknown {} element <{}>. Did you mean <{}>? {} element names are case-sensitive.",
+            label, elem.name, correct, label
+        ),
+        None if elem.namespace == Namespace::Svg => format!(
+            "<{}> is not an SVG element. Wrap HTML content inside SVG in <foreignObject>.",
+            elem.name
+        ),
+        None => format!("<{}> is not a MathML element.", elem.name),
+    };
+    Some(quote_spanned! { elem.span =>
+        compile_error!(#msg);
+    })
+}
+
+/// Rule 2: Attributes inside <svg>/<math> must belong to that namespace
+pub fn validate_foreign_attribute(namespace: Namespace, attr: &Attribute) -> Option<TokenStream> {
+    let (allowed, label) = match namespace {
+        Namespace::Html => return None,
+        Namespace::Svg => (SVG_ATTRIBUTES, "SVG"),
+        Namespace::MathMl => (MATHML_ATTRIBUTES, "MathML"),
+    };
+    let name = attr.name.as_str();
+
+    // Same escape hatches as HTML: hyphenated (stroke-width, data-*, aria-*, az-*),
+    // event handlers and namespaced names (xlink:href, xml:lang)
+    if name.contains('-') || name.starts_with("on") || name.contains(':') {
+        return None;
+    }
+    if allowed.contains(&name) {
+        return None;
+    }
+
+    let msg = match case_insensitive_match(name, allowed) {
+        Some(correct) => format!(
+            "Unknown {} attribute '{}'. Did you mean '{}'? {} attribute names are case-sensitive.",
+            label, name, correct, label
+        ),
+        None => format!(
+            "Unknown {} attribute '{}'. Check for typos, or use a data-* attribute.",
+            label, name
+        ),
+    };
+    Some(quote_spanned! { attr.span =>
+        compile_error!(#msg);
+    })
+}
+
+fn case_insensitive_match<'a>(name: &str, allowed: &[&'a str]) -> Option<&'a str> {
+    allowed
+        .iter()
+        .find(|candidate| candidate.eq_ignore_ascii_case(name))
+        .copied()
+}
@@ -1,9 +1,11 @@
 use crate::css::{extract_selectors, rename_css_selectors};
-use heck::ToSnakeCase;
+use crate::token_parser::{Block, Node};
+use heck::{ToShoutySnakeCase, ToSnakeCase};
 use lightningcss::stylesheet::{ParserOptions, StyleSheet};
-use proc_macro2::{TokenStream, TokenTree};
+use proc_macro2::{LineColumn, TokenStream, TokenTree};
 use quote::{format_ident, quote};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use syn::parse::{Parse, ParseStream};
 use syn::{braced, parse2, token, Ident, LitStr, Token};
@@ -13,15 +15,23 @@ pub struct StyleOutput {
     pub css: String,
 }
 
-// AST for our style! macro
+// AST for our style! macro, in source order
 struct StyleInput {
-    rules: Vec<StyleRule>,
-    at_rules: Vec<AtRule>,
+    items: Vec<StyleItem>,
+}
+
+enum StyleItem {
+    Rule(StyleRule),
+    AtRule(AtRule),
+    Property(StyleProperty),
+    Composes(Composes),
 }
 
 struct AtRule {
+    span: proc_macro2::Span, // of the name
     name: String,
-    content: String,
+    prelude: TokenStream,
+    block: Option<StyleBlock>, // None for statements like `@layer base, theme;`
 }
 
 struct StyleRule {
@@ -29,74 +39,347 @@ struct StyleRule {
     block: StyleBlock,
 }
 
+// Declarations plus nested rules (`&:hover { ... }`) and at-rules (`@media ... { ... }`)
 struct StyleBlock {
-    properties: Vec<StyleProperty>,
+    items: Vec<StyleItem>,
+}
+
+// `composes: chip chip_active from tag_component;` adds classes that another
+// component exports with @export to this rule's class
+struct Composes {
+    names: Vec<Ident>,
+    from: syn::Path,
+    span: proc_macro2::Span,
 }
 
 struct StyleProperty {
     name: String,
     value: String,
     span: proc_macro2::Span,
+    value_span: proc_macro2::Span,
 }
 
 impl Parse for StyleInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut rules = Vec::new();
-        let mut at_rules = Vec::new();
-
+        let mut items = Vec::new();
         while !input.is_empty() {
             // Check for @ rules
             if input.peek(Token![@]) {
-                let at_rule = input.parse::<AtRule>()?;
-                at_rules.push(at_rule);
+                let at_rule: AtRule = input.parse()?;
+                check_top_level_dark(&at_rule)?;
+                items.push(StyleItem::AtRule(at_rule));
             } else {
-                rules.push(input.parse()?);
+                items.push(StyleItem::Rule(input.parse()?));
             }
         }
-        Ok(StyleInput { rules, at_rules })
+        check_composes(&items, true)?;
+        check_exports(&items)?;
+        Ok(StyleInput { items })
+    }
+}
+
+impl StyleInput {
+    fn to_css(&self) -> String {
+        let mut css = String::new();
+        write_items(&self.items, Position::TopLevel(None), &mut css);
+        css
     }
 }
 
 impl Parse for AtRule {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         input.parse::<Token![@]>()?;
-        let name = input.parse::<Ident>()?.to_string();
 
-        // Parse everything until we hit a semicolon or end
-        let mut content = String::new();
-        let mut depth = 0;
-        let mut found_opening_brace = false;
+        // Names may contain dashes (@font-face, @custom-media)
+        let first = input.parse::<Ident>()?;
+        let span = first.span();
+        let mut name = first.to_string();
+        while input.peek(Token![-]) && input.peek2(Ident) {
+            input.parse::<Token![-]>()?;
+            name.push('-');
+            name.push_str(&input.parse::<Ident>()?.to_string());
+        }
 
-        while !input.is_empty() {
-            let fork = input.fork();
-            let tt: TokenTree = fork.parse()?;
-            let token_str = tt.to_string();
-
-            // Handle braces for nested structures
-            if token_str == "{" {
-                depth += 1;
-                found_opening_brace = true;
-            } else if token_str == "}" {
-                depth -= 1;
+        // Prelude runs until the block or the closing semicolon
+        let mut prelude = TokenStream::new();
+        while !input.is_empty() && !input.peek(token::Brace) && !input.peek(Token![;]) {
+            prelude.extend(std::iter::once(input.parse::<TokenTree>()?));
+        }
+
+        if name == "used" {
+            used_names(&prelude)?;
+        }
+        if name == "inherited" || name == "provides" {
+            variable_names(&name, &prelude)?;
+        }
+        if name == "dark" && !prelude.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &prelude,
+                "@dark takes no condition: `@dark { .card { color: \"white\"; } }`",
+            ));
+        }
+
+        let block = if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            Some(content.parse()?)
+        } else if name == "dark" {
+            return Err(input.error("Expected `{ ... }` after @dark"));
+        } else {
+            // Consume trailing semicolon if present
+            if input.peek(Token![;]) {
+                input.parse::<Token![;]>()?;
             }
+            None
+        };
 
-            // Stop when we've closed all braces and hit a semicolon
-            if depth == 0 && input.peek(Token![;]) {
-                break;
+        Ok(AtRule {
+            span,
+            name,
+            prelude,
+            block,
+        })
+    }
+}
+
+// `@used(is_open, is_active);` names classes that are only picked at runtime
+fn used_names(prelude: &TokenStream) -> syn::Result<Vec<Ident>> {
+    let usage = "Expected a list of class names: `@used(is_open, is_active);`";
+    let mut tokens = prelude.clone().into_iter();
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Group(group)), None)
+            if group.delimiter() == proc_macro2::Delimiter::Parenthesis =>
+        {
+            let names = syn::parse::Parser::parse2(
+                syn::punctuated::Punctuated::<Ident, Token![,]>::parse_terminated,
+                group.stream(),
+            )
+            .map_err(|e| syn::Error::new(e.span(), usage))?;
+            Ok(names.into_iter().collect())
+        }
+        _ => Err(syn::Error::new_spanned(prelude, usage)),
+    }
+}
+
+// `@inherited(--accent, --gap);` names CSS variables set by an ancestor component,
+// `@provides(--accent);` ones this component sets inline for its children
+fn variable_names(
+    at_rule: &str,
+    prelude: &TokenStream,
+) -> syn::Result<Vec<(String, proc_macro2::Span)>> {
+    let usage = format!(
+        "Expected a list of CSS variables: `@{}(--accent, --gap);`",
+        at_rule
+    );
+    let mut tokens = prelude.clone().into_iter();
+    let group = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Group(group)), None)
+            if group.delimiter() == proc_macro2::Delimiter::Parenthesis =>
+        {
+            group
+        }
+        _ => return Err(syn::Error::new_spanned(prelude, &usage)),
+    };
+
+    // `--card-accent` arrives as `- - card - accent`, so glue each comma-separated part
+    let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+    let mut names = Vec::new();
+    for part in tokens.split(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == ',')) {
+        let Some(first) = part.first() else {
+            continue;
+        };
+        let mut name = String::new();
+        for token in part {
+            match token {
+                TokenTree::Punct(p) if p.as_char() == '-' => name.push('-'),
+                TokenTree::Ident(_) | TokenTree::Literal(_) => name.push_str(&token.to_string()),
+                _ => return Err(syn::Error::new(token.span(), &usage)),
             }
+        }
+        if name.len() <= 2 || !name.starts_with("--") || name.ends_with('-') {
+            return Err(syn::Error::new(first.span(), &usage));
+        }
+        names.push((name, first.span()));
+    }
+    Ok(names)
+}
 
-            // Consume the token
-            let tt: TokenTree = input.parse()?;
-            content.push_str(&tt.to_string());
+// composes: only in a top-level rule for one class (`.badge { ... }`), whose
+// binding then holds the composed classes too
+fn check_composes(items: &[StyleItem], top_level: bool) -> syn::Result<()> {
+    for item in items {
+        match item {
+            StyleItem::Rule(rule) => {
+                let single_class = top_level && single_class(&rule.selectors).is_some();
+                for inner in &rule.block.items {
+                    match inner {
+                        StyleItem::Composes(composes) if !single_class => {
+                            return Err(syn::Error::new(
+                                composes.span,
+                                "composes is only allowed in a top-level rule for a single class: `.badge { composes: chip from tag_component; }`",
+                            ));
+                        }
+                        StyleItem::Rule(_) | StyleItem::AtRule(_) => {
+                            check_composes(std::slice::from_ref(inner), false)?
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            StyleItem::AtRule(at_rule) => {
+                if let Some(block) = &at_rule.block {
+                    check_composes(&block.items, false)?;
+                }
+            }
+            StyleItem::Composes(composes) => {
+                return Err(syn::Error::new(
+                    composes.span,
+                    "composes must be inside a class rule: `.badge { composes: chip from tag_component; }`",
+                ));
+            }
+            StyleItem::Property(_) => {}
         }
+    }
+    Ok(())
+}
 
-        // Consume trailing semicolon if present
-        if input.peek(Token![;]) {
-            input.parse::<Token![;]>()?;
+// `.badge` -> Some("badge")
+fn single_class(selectors: &TokenStream) -> Option<String> {
+    let tokens: Vec<TokenTree> = selectors.clone().into_iter().collect();
+    match tokens.as_slice() {
+        [TokenTree::Punct(dot), TokenTree::Ident(name)] if dot.as_char() == '.' => {
+            Some(name.to_string())
         }
+        _ => None,
+    }
+}
 
-        Ok(AtRule { name, content })
+// An exported class is shared under its own name, so none of its rules may
+// stay local to the component
+fn check_exports(items: &[StyleItem]) -> syn::Result<()> {
+    let mut exported = StyleSelectors::default();
+    let mut local = StyleSelectors::default();
+    for item in items {
+        match item {
+            StyleItem::AtRule(at_rule) if at_rule.name == "export" => {
+                if !at_rule.prelude.is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        &at_rule.prelude,
+                        "@export takes no name: `@export { .chip { ... } }`",
+                    ));
+                }
+                let Some(block) = &at_rule.block else {
+                    return Err(syn::Error::new(
+                        at_rule.span,
+                        "Expected `{ ... }` after @export",
+                    ));
+                };
+                for inner in &block.items {
+                    if let StyleItem::Property(prop) = inner {
+                        return Err(syn::Error::new(
+                            prop.span,
+                            "@export contains rules, not declarations: `@export { .chip { ... } }`",
+                        ));
+                    }
+                }
+                collect_selectors(&block.items, &mut exported);
+            }
+            _ => collect_selectors(std::slice::from_ref(item), &mut local),
+        }
+    }
+    let exported: std::collections::HashSet<_> =
+        exported.classes.into_iter().map(|(name, _)| name).collect();
+    for (name, span) in local.classes {
+        if exported.contains(&name) {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "'.{}' is exported with @export, so all of its rules must be inside @export",
+                    name
+                ),
+            ));
+        }
     }
+    Ok(())
+}
+
+// Only class names are renamed, so a top-level selector in a scoped block must
+// name a class or an id to stay inside the component. Nested rules are
+// relative to their parent, and keyframe selectors aren't element selectors.
+fn check_scoped_selectors(items: &[StyleItem]) -> syn::Result<()> {
+    for item in items {
+        match item {
+            StyleItem::Rule(rule) => {
+                for selector in split_selectors(&rule.selectors) {
+                    if !names_class_or_id(&selector) {
+                        return Err(syn::Error::new_spanned(
+                            &selector,
+                            format!(
+                                "Selector '{}' in a scoped <style> would match elements outside this component. Add one of its classes or an id (`.content {}`), or move the rule to <style global>",
+                                tokens_to_css(&selector),
+                                tokens_to_css(&selector)
+                            ),
+                        ));
+                    }
+                }
+            }
+            StyleItem::AtRule(at_rule) if !at_rule.name.ends_with("keyframes") => {
+                if let Some(block) = &at_rule.block {
+                    check_scoped_selectors(&block.items)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// `.a, p` -> [`.a`, `p`]; commas inside `:is(...)` stay in their group
+fn split_selectors(selectors: &TokenStream) -> Vec<TokenStream> {
+    let mut parts = vec![TokenStream::new()];
+    for token in selectors.clone() {
+        match &token {
+            TokenTree::Punct(p) if p.as_char() == ',' => parts.push(TokenStream::new()),
+            _ => parts.last_mut().unwrap().extend([token]),
+        }
+    }
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+// `.card p` / `#main` yes; `p`, `:root`, `p:not(.done)` no
+fn names_class_or_id(selector: &TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = selector.clone().into_iter().collect();
+    tokens.windows(2).any(|pair| {
+        matches!(
+            pair,
+            [TokenTree::Punct(p), TokenTree::Ident(_)] if p.as_char() == '.' || p.as_char() == '#'
+        )
+    })
+}
+
+// Outside a rule, @dark needs selectors to prefix: declarations belong in a rule
+// (`@dark { .card { ... } }`) or in an @dark nested inside one
+fn check_top_level_dark(at_rule: &AtRule) -> syn::Result<()> {
+    let Some(block) = &at_rule.block else {
+        return Ok(());
+    };
+    for item in &block.items {
+        match item {
+            StyleItem::Property(prop) if at_rule.name == "dark" => {
+                return Err(syn::Error::new(
+                    prop.span,
+                    format!(
+                        "'{}' in a top-level @dark needs a selector: `@dark {{ .card {{ {}: ...; }} }}`, or move @dark inside the rule",
+                        prop.name, prop.name
+                    ),
+                ));
+            }
+            StyleItem::AtRule(inner) => check_top_level_dark(inner)?,
+            _ => {}
+        }
+    }
+    Ok(())
 }
 
 impl Parse for StyleRule {
@@ -189,11 +472,64 @@ fn validate_selectors(tokens: &TokenStream) -> syn::Result<()> {
 
 impl Parse for StyleBlock {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut properties = Vec::new();
+        let mut items = Vec::new();
         while !input.is_empty() {
-            properties.push(input.parse()?);
+            if input.peek(Token![@]) {
+                let at_rule: AtRule = input.parse()?;
+                if at_rule.name == "export" {
+                    return Err(syn::Error::new(
+                        at_rule.span,
+                        "@export must be at the top level of <style>",
+                    ));
+                }
+                items.push(StyleItem::AtRule(at_rule));
+            } else if input.peek(Ident)
+                && input.peek2(Token![:])
+                && input.fork().parse::<Ident>()? == "composes"
+            {
+                items.push(StyleItem::Composes(input.parse()?));
+            } else if starts_nested_rule(input) {
+                items.push(StyleItem::Rule(input.parse()?));
+            } else {
+                items.push(StyleItem::Property(input.parse()?));
+            }
+        }
+        Ok(StyleBlock { items })
+    }
+}
+
+// A nested rule reaches its `{ ... }` before any `;` (values are string literals, never blocks)
+fn starts_nested_rule(input: ParseStream) -> bool {
+    let fork = input.fork();
+    while let Ok(tt) = fork.parse::<TokenTree>() {
+        match tt {
+            TokenTree::Group(g) if g.delimiter() == proc_macro2::Delimiter::Brace => return true,
+            TokenTree::Punct(p) if p.as_char() == ';' => return false,
+            _ => {}
         }
-        Ok(StyleBlock { properties })
+    }
+    false
+}
+
+impl Parse for Composes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let usage = "Expected `composes: chip from tag_component;` (classes another component shares with @export)";
+        let span = input.parse::<Ident>()?.span();
+        input.parse::<Token![:]>()?;
+
+        let mut names = Vec::new();
+        while input.peek(Ident) && input.fork().parse::<Ident>()? != "from" {
+            names.push(input.parse::<Ident>()?);
+        }
+        let keyword = input
+            .parse::<Ident>()
+            .map_err(|e| syn::Error::new(e.span(), usage))?;
+        if names.is_empty() || keyword != "from" {
+            return Err(syn::Error::new(keyword.span(), usage));
+        }
+        let from = syn::Path::parse_mod_style(input)?;
+        input.parse::<Token![;]>()?;
+        Ok(Composes { names, from, span })
     }
 }
 
@@ -259,6 +595,7 @@ impl Parse for StyleProperty {
             name,
             value,
             span: start_span,
+            value_span: value_start_span,
         })
     }
 }
@@ -270,21 +607,9 @@ fn validate_css_value(property: &str, value: &str) -> Result<(), String> {
     // Check for spaces in single-word values (common typo)
     let trimmed = value.trim();
 
-    if value.contains("white") {
-        eprintln!(
-            "DEBUG: validate_css_value checking '{}' for property '{}'. contains space? {}",
-            value,
-            property,
-            value.contains(' ')
-        );
-    }
-
     if !is_multi_word_property(property) {
         // Properties that should be single tokens (no spaces)
         if trimmed.contains(' ') && !is_valid_space_in_value(property, trimmed) {
-            if value.contains("white") {
-                eprintln!("DEBUG: validate_css_value RETURNING ERROR for '{}'", value);
-            }
             return Err(format!(
                 "Unexpected space in value '{}'. Did you mean '{}'?",
                 value,
@@ -293,13 +618,6 @@ fn validate_css_value(property: &str, value: &str) -> Result<(), String> {
         }
     }
 
-    if value.contains("white") {
-        eprintln!(
-            "DEBUG: validate_css_value PASSED space check for '{}'",
-            value
-        );
-    }
-
     // Check for invalid units
     if let Some(err) = validate_units(trimmed) {
         return Err(err);
@@ -448,16 +766,10 @@ fn validate_hex_color(value: &str) -> Option<String> {
 
 /// Process global style macro - validates but doesn't scope or generate bindings
 pub fn process_global_style_macro(input: TokenStream) -> StyleOutput {
-    eprintln!("DEBUG GLOBAL: input tokens: {}", input);
-
     // 1. Parse the input
-    let style_input: StyleInput = match parse2(input.clone()) {
-        Ok(input) => {
-            eprintln!("DEBUG GLOBAL: Parse SUCCESS");
-            input
-        }
+    let style_input: StyleInput = match parse2(input) {
+        Ok(input) => input,
         Err(err) => {
-            eprintln!("DEBUG GLOBAL: Parse FAILED: {}", err);
             return StyleOutput {
                 bindings: err.to_compile_error(),
                 css: String::new(),
@@ -465,45 +777,27 @@ pub fn process_global_style_macro(input: TokenStream) -> StyleOutput {
         }
     };
 
-    // 2. Generate raw CSS (validation happens during parsing above)
-    eprintln!(
-        "DEBUG GLOBAL: number of parsed rules: {}, at_rules: {}",
-        style_input.rules.len(),
-        style_input.at_rules.len()
-    );
-    let mut raw_css = String::new();
-
-    // Add @rules first
-    for at_rule in &style_input.at_rules {
-        raw_css.push_str("@");
-        raw_css.push_str(&at_rule.name);
-        raw_css.push_str(" ");
-        raw_css.push_str(&at_rule.content);
-        if !at_rule.content.trim().ends_with('}') {
-            raw_css.push_str(";");
-        }
-        raw_css.push_str(" ");
+    let export = style_input.items.iter().find_map(|item| match item {
+        StyleItem::AtRule(at_rule) if at_rule.name == "export" => Some(at_rule),
+        _ => None,
+    });
+    if let Some(export) = export {
+        return StyleOutput {
+            bindings: syn::Error::new(
+                export.span,
+                "@export is for scoped <style> blocks; classes in <style global> are already shared",
+            )
+            .to_compile_error(),
+            css: String::new(),
+        };
     }
 
-    // Add regular rules
-    for rule in &style_input.rules {
-        let selector_str = tokens_to_css_string(&rule.selectors);
-
-        raw_css.push_str(&selector_str);
-        raw_css.push_str(" { ");
-        for prop in &rule.block.properties {
-            raw_css.push_str(&format!("{}: {}; ", prop.name, prop.value));
-        }
-        raw_css.push_str("} ");
-    }
+    // 2. Generate raw CSS (validation happens during parsing above)
+    let raw_css = style_input.to_css();
 
     // 3. Extract classes and IDs for bindings (even though not scoped)
     let (classes, ids) = extract_selectors(&raw_css);
 
-    eprintln!("DEBUG GLOBAL: raw_css: '{}'", raw_css);
-    eprintln!("DEBUG GLOBAL: extracted classes: {:?}", classes);
-    eprintln!("DEBUG GLOBAL: extracted ids: {:?}", ids);
-
     // 4. Generate Bindings for both classes and IDs (without scoping)
     let mut bindings = TokenStream::new();
 
@@ -526,7 +820,11 @@ pub fn process_global_style_macro(input: TokenStream) -> StyleOutput {
         });
     }
 
-    eprintln!("DEBUG GLOBAL: bindings tokens: {}", bindings);
+    crate::debug::dump_style(
+        &format!("global-{}", class_scope_id(&raw_css)),
+        &raw_css,
+        &bindings,
+    );
 
     // 5. Return unscoped CSS with bindings
     StyleOutput {
@@ -548,37 +846,45 @@ pub fn process_style_macro(input: TokenStream) -> StyleOutput {
         }
     };
 
+    if let Err(err) = check_scoped_selectors(&style_input.items) {
+        return StyleOutput {
+            bindings: err.to_compile_error(),
+            css: String::new(),
+        };
+    }
+
     // 2. Reconstruct CSS string (with quotes removed from values)
     let raw_css = reconstruct_css_from_tokens(input_clone);
 
     // 3. Generate Scope ID
-    let mut hasher = DefaultHasher::new();
-    raw_css.hash(&mut hasher);
-    let hash = hasher.finish();
-    let scope_id = format!("s{:x}", hash);
+    let scope_id = class_scope_id(&raw_css);
 
     // 4. Extract classes and IDs for bindings
     let (classes, ids) = extract_selectors(&raw_css);
 
-    eprintln!("DEBUG: raw_css: '{}'", raw_css);
-    eprintln!("DEBUG: extracted classes: {:?}", classes);
-    eprintln!("DEBUG: extracted ids: {:?}", ids);
-
     // 5. Scope the CSS (rename classes)
     let scoped_css = rename_css_selectors(&raw_css, &scope_id);
-    eprintln!("DEBUG: scoped_css: '{}'", scoped_css);
 
     // 6. Generate Bindings for both classes and IDs
     let mut bindings = TokenStream::new();
+    let composed = composed_classes(&style_input);
 
     // Generate class bindings
     for class in classes {
         let snake_name = class.to_snake_case();
         let ident = format_ident!("{}", snake_name);
-        let scoped_class = format!("{}-{}", class, scope_id);
+        let value = class_value(&format!("{}-{}", class, scope_id), composed.get(&class));
+
+        bindings.extend(quote! {
+            let #ident = #value;
+        });
+    }
 
+    // Exported classes are bound like local ones
+    for (class, scoped) in exported_styles_of(&style_input).map_or_else(Vec::new, |e| e.classes) {
+        let ident = format_ident!("{}", class.to_snake_case());
         bindings.extend(quote! {
-            let #ident = #scoped_class;
+            let #ident = #scoped;
         });
     }
 
@@ -591,7 +897,7 @@ pub fn process_style_macro(input: TokenStream) -> StyleOutput {
         });
     }
 
-    eprintln!("DEBUG: bindings tokens: {}", bindings);
+    crate::debug::dump_style(&scope_id, &scoped_css, &bindings);
 
     StyleOutput {
         bindings,
@@ -599,116 +905,488 @@ pub fn process_style_macro(input: TokenStream) -> StyleOutput {
     }
 }
 
-/// Reconstruct CSS string from TokenStream (parsing and formatting)
-pub fn reconstruct_css_from_tokens(input: TokenStream) -> String {
-    // 1. Parse the input
-    let style_input: StyleInput = match parse2(input) {
-        Ok(input) => input,
-        Err(e) => {
-            eprintln!("DEBUG: reconstruct_css_from_tokens failed to parse: {}", e);
-            return String::new();
+// Suffix for the block's class names, from its CSS so it is stable across builds
+fn class_scope_id(raw_css: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    raw_css.hash(&mut hasher);
+    format!("s{:x}", hasher.finish())
+}
+
+/// Class names of a scoped style block, each with the value its binding holds
+/// (`card` -> `"card-s1a2b"`, with any `composes:` classes appended)
+pub fn scoped_classes(input: TokenStream) -> Vec<(String, TokenStream)> {
+    let Ok(style_input) = parse2::<StyleInput>(input) else {
+        return Vec::new();
+    };
+    let raw_css = style_input.to_css();
+    let scope_id = class_scope_id(&raw_css);
+    let composed = composed_classes(&style_input);
+    let (classes, _) = extract_selectors(&raw_css);
+    classes
+        .into_iter()
+        .map(|class| {
+            let value = class_value(&format!("{}-{}", class, scope_id), composed.get(&class));
+            (class, value)
+        })
+        .collect()
+}
+
+// Classes of top-level rules with `composes:`, and what they compose
+fn composed_classes(style_input: &StyleInput) -> HashMap<String, Vec<(Ident, syn::Path)>> {
+    let mut composed: HashMap<String, Vec<(Ident, syn::Path)>> = HashMap::new();
+    for item in &style_input.items {
+        let StyleItem::Rule(rule) = item else {
+            continue;
+        };
+        let Some(class) = single_class(&rule.selectors) else {
+            continue;
+        };
+        for inner in &rule.block.items {
+            if let StyleItem::Composes(composes) = inner {
+                let shared = composes
+                    .names
+                    .iter()
+                    .map(|name| (name.clone(), composes.from.clone()));
+                composed.entry(class.clone()).or_default().extend(shared);
+            }
         }
+    }
+    composed
+}
+
+// `chip from tag_component` -> `tag_component::exports::CHIP`, spanned at `chip`
+// so a class the component doesn't export is reported there
+fn shared_class_path(name: &Ident, from: &syn::Path) -> TokenStream {
+    let constant = Ident::new(&name.to_string().to_shouty_snake_case(), name.span());
+    quote! { #from::exports::#constant }
+}
+
+// A class binding's value: its scoped name, plus `composes:` classes joined at
+// compile time so the binding stays a `&'static str`
+fn class_value(scoped: &str, composed: Option<&Vec<(Ident, syn::Path)>>) -> TokenStream {
+    let Some(composed) = composed else {
+        return quote! { #scoped };
     };
+    let parts = composed
+        .iter()
+        .map(|(name, from)| shared_class_path(name, from));
+    quote! {
+        {
+            const PARTS: &[&str] = &[#scoped, #(#parts.class),*];
+            const BYTES: [u8; azumi::styles::joined_len(PARTS)] = azumi::styles::join_classes(PARTS);
+            const CLASSES: &str = azumi::styles::class_str(&BYTES);
+            CLASSES
+        }
+    }
+}
 
+/// Rules a style block shares with `@export { ... }`. Their classes get a suffix
+/// of their own but no scope attribute, so other components' elements match.
+pub struct ExportedStyles {
+    pub classes: Vec<(String, String)>,
+    pub css: String,
+}
+
+pub fn exported_styles(input: TokenStream) -> Option<ExportedStyles> {
+    exported_styles_of(&parse2::<StyleInput>(input).ok()?)
+}
+
+fn exported_styles_of(style_input: &StyleInput) -> Option<ExportedStyles> {
     let mut raw_css = String::new();
+    for item in &style_input.items {
+        if let StyleItem::AtRule(at_rule) = item {
+            if let (true, Some(block)) = (at_rule.name == "export", &at_rule.block) {
+                write_items(&block.items, Position::TopLevel(None), &mut raw_css);
+            }
+        }
+    }
+    if raw_css.is_empty() {
+        return None;
+    }
+    let suffix = class_scope_id(&raw_css);
+    let (classes, _) = extract_selectors(&raw_css);
+    let mut classes: Vec<_> = classes
+        .into_iter()
+        .map(|class| {
+            let scoped = format!("{}-{}", class, suffix);
+            (class, scoped)
+        })
+        .collect();
+    classes.sort();
+    Some(ExportedStyles {
+        classes,
+        css: rename_css_selectors(&raw_css, &suffix),
+    })
+}
+
+/// Statements for the render closure that write the stylesheet this block
+/// exports and the ones its `composes:` classes come from
+pub fn shared_stylesheets(input: TokenStream) -> TokenStream {
+    let Ok(style_input) = parse2::<StyleInput>(input) else {
+        return TokenStream::new();
+    };
+    let mut output = TokenStream::new();
+    if let Some(exported) = exported_styles_of(&style_input) {
+        output.extend(crate::emit_stylesheet(
+            &exported.css,
+            true,
+            "<style>{}</style>",
+        ));
+    }
+    let mut composed: Vec<_> = composed_classes(&style_input)
+        .into_values()
+        .flatten()
+        .collect();
+    if !composed.is_empty() {
+        composed.sort_by_key(|(name, _)| name.to_string());
+        let shared = composed
+            .iter()
+            .map(|(name, from)| shared_class_path(name, from));
+        output.extend(quote! {
+            azumi::styles::write_shared(f, &[#(&#shared),*])?;
+        });
+    }
+    output
+}
 
-    // Add @rules first
-    for at_rule in &style_input.at_rules {
-        raw_css.push_str("@");
-        raw_css.push_str(&at_rule.name);
-        raw_css.push_str(" ");
-        raw_css.push_str(&at_rule.content);
-        if !at_rule.content.trim().ends_with('}') {
-            raw_css.push_str(";");
+/// Contents of the scoped <style> blocks in a template, in order
+pub fn scoped_style_blocks(nodes: &[Node], styles: &mut Vec<proc_macro2::TokenStream>) {
+    for node in nodes {
+        match node {
+            Node::Element(elem) => scoped_style_blocks(&elem.children, styles),
+            Node::Fragment(frag) => scoped_style_blocks(&frag.children, styles),
+            Node::Block(block) => match block {
+                Block::Style(style) if !style.is_global => styles.push(style.content.clone()),
+                Block::If(if_block) => {
+                    scoped_style_blocks(&if_block.then_branch, styles);
+                    if let Some(else_branch) = &if_block.else_branch {
+                        scoped_style_blocks(else_branch, styles);
+                    }
+                }
+                Block::For(for_block) => {
+                    scoped_style_blocks(&for_block.body, styles);
+                    if let Some(empty_branch) = &for_block.empty_branch {
+                        scoped_style_blocks(empty_branch, styles);
+                    }
+                }
+                Block::Match(match_block) => {
+                    for arm in &match_block.arms {
+                        scoped_style_blocks(&arm.body, styles);
+                    }
+                }
+                Block::Call(call_block) => scoped_style_blocks(&call_block.children, styles),
+                _ => {}
+            },
+            _ => {}
         }
-        raw_css.push_str(" ");
     }
+}
 
-    // Add regular rules
-    for rule in &style_input.rules {
-        let selector_str = tokens_to_css_string(&rule.selectors);
+/// Custom properties a style block declares (`--gap: "1rem";`) or provides to
+/// its children (`@provides(--gap);`) and the `var(--name)` references in its
+/// values, for checking against the theme
+pub struct CssVariables {
+    pub declared: Vec<String>,
+    pub used: Vec<(String, proc_macro2::Span)>,
+    pub with_fallback: Vec<String>,
+    pub provided: Vec<String>,
+}
 
-        raw_css.push_str(&selector_str);
-        raw_css.push_str(" { ");
-        for prop in &rule.block.properties {
-            raw_css.push_str(&format!("{}: {}; ", prop.name, prop.value));
+pub fn css_variables(input: TokenStream) -> CssVariables {
+    fn walk(items: &[StyleItem], vars: &mut CssVariables) {
+        for item in items {
+            match item {
+                StyleItem::Rule(rule) => walk(&rule.block.items, vars),
+                // Set by an ancestor, so they count as declared here
+                StyleItem::AtRule(at_rule) if at_rule.name == "inherited" => {
+                    let names = variable_names(&at_rule.name, &at_rule.prelude).unwrap_or_default();
+                    vars.declared
+                        .extend(names.into_iter().map(|(name, _)| name));
+                }
+                // Set inline here and read by children
+                StyleItem::AtRule(at_rule) if at_rule.name == "provides" => {
+                    let names = variable_names(&at_rule.name, &at_rule.prelude).unwrap_or_default();
+                    vars.provided
+                        .extend(names.into_iter().map(|(name, _)| name));
+                }
+                StyleItem::AtRule(at_rule) => {
+                    if let Some(block) = &at_rule.block {
+                        walk(&block.items, vars);
+                    }
+                }
+                StyleItem::Composes(_) => {}
+                StyleItem::Property(prop) => {
+                    if prop.name.starts_with("--") {
+                        vars.declared.push(prop.name.clone());
+                    }
+                    for (name, fallback) in crate::theme::var_references(&prop.value) {
+                        if fallback {
+                            vars.with_fallback.push(name);
+                        } else {
+                            vars.used.push((name, prop.value_span));
+                        }
+                    }
+                }
+            }
         }
-        raw_css.push_str("} ");
     }
 
-    raw_css
+    let mut vars = CssVariables {
+        declared: Vec::new(),
+        used: Vec::new(),
+        with_fallback: Vec::new(),
+        provided: Vec::new(),
+    };
+    // Parse errors are reported by process_style_macro
+    if let Ok(style_input) = parse2::<StyleInput>(input) {
+        walk(&style_input.items, &mut vars);
+    }
+    vars
 }
 
-fn tokens_to_css_string(tokens: &TokenStream) -> String {
-    let mut css = String::new();
-    let mut last_char_was_hyphen = false;
-    let mut last_char_was_dot_or_hash = false;
+/// Class and id selectors a style block defines, each with the span of its
+/// first mention, and the names marked as used with `@used(...)`
+#[derive(Default)]
+pub struct StyleSelectors {
+    pub classes: Vec<(String, proc_macro2::Span)>,
+    pub ids: Vec<(String, proc_macro2::Span)>,
+    pub used: Vec<(String, proc_macro2::Span)>,
+}
 
-    for tt in tokens.clone() {
-        match tt {
-            TokenTree::Ident(ident) => {
-                // Add space if previous wasn't a special char that expects attachment
-                if !css.is_empty() && !last_char_was_hyphen && !last_char_was_dot_or_hash {
-                    css.push(' ');
+pub fn style_selectors(input: TokenStream, selectors: &mut StyleSelectors) {
+    // Parse errors are reported by process_style_macro
+    if let Ok(style_input) = parse2::<StyleInput>(input) {
+        collect_selectors(&style_input.items, selectors);
+    }
+}
+
+// Exported classes are left out: they are there for other components
+fn collect_selectors(items: &[StyleItem], selectors: &mut StyleSelectors) {
+    for item in items {
+        match item {
+            StyleItem::Rule(rule) => {
+                scan_selectors(rule.selectors.clone(), selectors);
+                collect_selectors(&rule.block.items, selectors);
+            }
+            StyleItem::AtRule(at_rule) if at_rule.name == "used" => {
+                for name in used_names(&at_rule.prelude).unwrap_or_default() {
+                    selectors.used.push((name.to_string(), name.span()));
+                }
+            }
+            StyleItem::AtRule(at_rule) if at_rule.name == "export" => {}
+            StyleItem::AtRule(at_rule) => {
+                if let Some(block) = &at_rule.block {
+                    collect_selectors(&block.items, selectors);
                 }
-                css.push_str(&ident.to_string());
-                last_char_was_hyphen = false;
-                last_char_was_dot_or_hash = false;
             }
-            TokenTree::Punct(punct) => {
-                let ch = punct.as_char();
-                if ch == '-' {
-                    // No space before hyphen (handled by loop logic: if previous was ident, we didn't add space? Wait.
-                    // If previous was ident, we are here.
-                    // We just append '-'.
-                    css.push(ch);
-                    last_char_was_hyphen = true;
-                    last_char_was_dot_or_hash = false;
-                } else if ch == '.' || ch == '#' || ch == ':' {
-                    // Add space before dot/hash if it's not the start?
-                    // Actually, for `.class`, we want space before dot if it's `div .class`.
-                    // But we decided to collapse spaces to fix `.class`.
-                    // So we just append.
-                    if !css.is_empty() && !last_char_was_hyphen && !last_char_was_dot_or_hash {
-                        // For now, let's NOT add space before dot/hash to ensure .class works.
-                        // This breaks `div .class` but fixes `.class`.
-                        // css.push(' ');
+            StyleItem::Property(_) | StyleItem::Composes(_) => {}
+        }
+    }
+}
+
+// `.name` and `#name`, including inside `:is(...)` / `:not(...)`
+fn scan_selectors(tokens: TokenStream, selectors: &mut StyleSelectors) {
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(tt) = iter.next() {
+        match tt {
+            TokenTree::Punct(p) if p.as_char() == '.' || p.as_char() == '#' => {
+                if let Some(TokenTree::Ident(ident)) = iter.peek() {
+                    let entry = (ident.to_string(), ident.span());
+                    if p.as_char() == '.' {
+                        selectors.classes.push(entry);
+                    } else {
+                        selectors.ids.push(entry);
                     }
-                    css.push(ch);
-                    last_char_was_hyphen = false;
-                    last_char_was_dot_or_hash = true;
-                } else {
-                    // Other puncts (>, +, etc)
-                    css.push(ch);
-                    last_char_was_hyphen = false;
-                    last_char_was_dot_or_hash = false;
                 }
             }
-            TokenTree::Literal(lit) => {
-                if !css.is_empty() {
-                    css.push(' ');
+            TokenTree::Group(group) => scan_selectors(group.stream(), selectors),
+            _ => {}
+        }
+    }
+}
+
+/// Reconstruct CSS string from TokenStream (parsing and formatting)
+pub fn reconstruct_css_from_tokens(input: TokenStream) -> String {
+    // Parse errors are reported where the block is processed (process_style_macro)
+    match parse2::<StyleInput>(input) {
+        Ok(style_input) => style_input.to_css(),
+        Err(_) => String::new(),
+    }
+}
+
+// Where items are written: at the top level (optionally under an @dark ancestor
+// selector that prefixes every rule), or inside a rule where `&` is the parent
+#[derive(Clone, Copy)]
+enum Position<'a> {
+    TopLevel(Option<&'a str>),
+    InRule,
+}
+
+fn write_items(items: &[StyleItem], position: Position, css: &mut String) {
+    for item in items {
+        match item {
+            StyleItem::Rule(rule) => {
+                let selectors = tokens_to_css(&rule.selectors);
+                match position {
+                    Position::TopLevel(Some(ancestor)) => {
+                        css.push_str(&prefix_selectors(&selectors, ancestor))
+                    }
+                    _ => css.push_str(&selectors),
                 }
-                css.push_str(&lit.to_string());
-                last_char_was_hyphen = false;
-                last_char_was_dot_or_hash = false;
+                css.push_str(" { ");
+                write_items(&rule.block.items, Position::InRule, css);
+                css.push_str("} ");
             }
-            TokenTree::Group(group) => {
-                if !css.is_empty() {
+            // Only read by the unused-selector and CSS variable checks
+            StyleItem::AtRule(at_rule)
+                if matches!(at_rule.name.as_str(), "used" | "inherited" | "provides") => {}
+            // Written as a separate, shared stylesheet (see exported_styles)
+            StyleItem::AtRule(at_rule) if at_rule.name == "export" => {}
+            // Composed classes come with their binding, not CSS
+            StyleItem::Composes(_) => {}
+            StyleItem::AtRule(at_rule) if at_rule.name == "dark" => {
+                let Some(block) = &at_rule.block else {
+                    continue;
+                };
+                write_dark(&block.items, position, css);
+            }
+            StyleItem::AtRule(at_rule) => {
+                css.push('@');
+                css.push_str(&at_rule.name);
+                if !at_rule.prelude.is_empty() {
                     css.push(' ');
+                    css.push_str(&tokens_to_css(&at_rule.prelude));
                 }
-                // Recurse? Or just to_string?
-                // Selectors usually don't have groups (parens maybe for :not()?)
-                // For :not(), we want :not(...).
-                // Let's just use to_string for groups for now.
-                css.push_str(&group.to_string());
-                last_char_was_hyphen = false;
-                last_char_was_dot_or_hash = false;
+                match &at_rule.block {
+                    Some(block) => {
+                        css.push_str(" { ");
+                        write_items(&block.items, position, css);
+                        css.push_str("} ");
+                    }
+                    None => css.push_str("; "),
+                }
+            }
+            StyleItem::Property(prop) => {
+                css.push_str(&format!("{}: {}; ", prop.name, prop.value));
             }
         }
     }
+}
+
+// `@dark { ... }` is written twice, for the OS preference and for an explicit
+// `data-theme="dark"` on an ancestor, with the same selectors as `theme!()`.
+// The ancestor has no classes, so renaming only touches the rule's own selectors.
+fn write_dark(items: &[StyleItem], position: Position, css: &mut String) {
+    use crate::theme::{DARK_PREFERENCE_MEDIA, DARK_PREFERENCE_SELECTOR, DARK_SELECTOR};
+
+    css.push_str(&format!("@media {} {{ ", DARK_PREFERENCE_MEDIA));
+    write_dark_variant(items, position, DARK_PREFERENCE_SELECTOR, css);
+    css.push_str("} ");
+    write_dark_variant(items, position, DARK_SELECTOR, css);
+}
+
+fn write_dark_variant(items: &[StyleItem], position: Position, ancestor: &str, css: &mut String) {
+    match position {
+        Position::InRule => {
+            css.push_str(&format!("{} & {{ ", ancestor));
+            write_items(items, Position::InRule, css);
+            css.push_str("} ");
+        }
+        // An @dark nested in a top-level one keeps the outer ancestor
+        Position::TopLevel(outer) => write_items(
+            items,
+            Position::TopLevel(Some(outer.unwrap_or(ancestor))),
+            css,
+        ),
+    }
+}
+
+// `.a, .b:is(.c, .d)` -> `X .a, X .b:is(.c, .d)`
+fn prefix_selectors(selectors: &str, ancestor: &str) -> String {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in selectors.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&selectors[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&selectors[start..]);
+    parts
+        .iter()
+        .map(|part| format!("{} {}", ancestor, part.trim()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Turn selector/prelude tokens back into CSS, keeping whitespace only where the
+/// source had it: `.card .title` is not `.card.title`, and `&:hover` stays joined.
+/// Quoted values are unquoted (`(max-width: "600px")`), except in `[attr="x"]`.
+fn tokens_to_css(tokens: &TokenStream) -> String {
+    let mut css = String::new();
+    write_tokens(tokens.clone(), None, false, &mut css);
     css
 }
 
+fn write_tokens(
+    tokens: TokenStream,
+    mut prev: Option<(LineColumn, LineColumn)>,
+    in_brackets: bool,
+    css: &mut String,
+) {
+    use proc_macro2::Delimiter;
+
+    let mut prev_is_word = false;
+    for tt in tokens {
+        let (start, end) = (tt.span().start(), tt.span().end());
+        let is_word = !matches!(tt, TokenTree::Punct(_));
+        let spaced = match prev {
+            Some((prev_start, prev_end)) if start.line > 0 && start != prev_start => {
+                prev_end != start
+            }
+            // No real positions (tokens parsed from a string share one span):
+            // separate consecutive words
+            Some(_) => prev_is_word && is_word,
+            None => false,
+        };
+        if spaced {
+            css.push(' ');
+        }
+        prev = Some((start, end));
+        prev_is_word = is_word;
+
+        match tt {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                css.push_str(open);
+                write_tokens(
+                    group.stream(),
+                    Some((group.span_open().start(), group.span_open().end())),
+                    group.delimiter() == Delimiter::Bracket,
+                    css,
+                );
+                css.push_str(close);
+            }
+            TokenTree::Literal(lit) => match syn::Lit::new(lit.clone()) {
+                syn::Lit::Str(s) if !in_brackets => css.push_str(&s.value()),
+                _ => css.push_str(&lit.to_string()),
+            },
+            other => css.push_str(&other.to_string()),
+        }
+    }
+}
+
 fn is_valid_css_property(name: &str) -> bool {
     if name.starts_with("--") {
         return true;
@@ -949,3 +1627,26 @@ fn is_valid_css_property(name: &str) -> bool {
 
     valid_properties.contains(&name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(css: &str) -> syn::Result<()> {
+        let input: StyleInput = syn::parse_str(css)?;
+        check_scoped_selectors(&input.items)
+    }
+
+    #[test]
+    fn test_scoped_selectors_need_a_class_or_id() {
+        assert!(check(r#".card p { color: "red"; } #main { margin: "0"; }"#).is_ok());
+        assert!(check(r#".card { p { color: "red"; } }"#).is_ok());
+        assert!(check(r#"@keyframes spin { from { opacity: "0"; } }"#).is_ok());
+
+        let err = check(r#".card, p { color: "red"; }"#).unwrap_err();
+        assert!(err.to_string().contains("Selector 'p'"));
+        assert!(check(r#"@media (max-width: "600px") { p { color: "red"; } }"#).is_err());
+        assert!(check(r#"p:not(.done) { color: "red"; }"#).is_err());
+        assert!(check(r#":root { color: "red"; }"#).is_err());
+    }
+}
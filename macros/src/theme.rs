@@ -0,0 +1,459 @@
+//! Design tokens: theme.toml next to Cargo.toml declares them once
+//!
+//! ```toml
+//! [color]
+//! primary = "#4f46e5"
+//! surface = { light = "#ffffff", dark = "#111827" }
+//!
+//! [space]
+//! md = "1rem"
+//! ```
+//!
+//! Each entry becomes a CSS variable named after its path (`--color-primary`).
+//! `theme!()` renders the `:root` CSS, and while theme.toml exists every
+//! `var(--x)` in <style> and every `style={ --x: ... }` is checked against it.
+//! Variables an ancestor component sets are declared with `@inherited(--x);`,
+//! and the ancestor lists the ones it sets inline with `@provides(--x);`.
+
+use crate::token_parser::{AttributeValue, Block, Node};
+use lightningcss::properties::{Property, PropertyId};
+use lightningcss::stylesheet::ParserOptions;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{quote, quote_spanned};
+use std::collections::{BTreeMap, HashSet};
+
+const THEME_FILE: &str = "theme.toml";
+
+/// Dark styles apply under the OS preference unless the page picks the light
+/// theme, or whenever it picks the dark one (`<html data-theme="dark">`)
+pub const DARK_PREFERENCE_MEDIA: &str = "(prefers-color-scheme: dark)";
+pub const DARK_PREFERENCE_SELECTOR: &str = ":root:not([data-theme=light])";
+pub const DARK_SELECTOR: &str = "[data-theme=dark]";
+
+// Groups whose values are type-checked, and the property that checks them
+const TYPED_GROUPS: &[(&str, &str)] = &[
+    ("color", "color"),
+    ("space", "padding"),
+    ("spacing", "padding"),
+    ("size", "width"),
+    ("font", "font-family"),
+    ("radius", "border-radius"),
+    ("shadow", "box-shadow"),
+];
+
+pub struct Theme {
+    full_path: String, // for include_str! change tracking
+    tokens: BTreeMap<String, Token>,
+}
+
+struct Token {
+    light: String,
+    dark: Option<String>,
+    property: Option<&'static str>, // None for untyped groups
+}
+
+pub fn expand_theme(input: TokenStream) -> TokenStream {
+    if !input.is_empty() {
+        let msg = format!(
+            "theme!() takes no arguments; tokens are read from {} next to Cargo.toml",
+            THEME_FILE
+        );
+        return quote! { compile_error!(#msg) }.into();
+    }
+
+    let theme = match load_theme() {
+        Ok(Some(theme)) => theme,
+        Ok(None) => {
+            let msg = format!(
+                "theme!() needs {} next to Cargo.toml, e.g.\n[color]\nprimary = \"#4f46e5\"",
+                THEME_FILE
+            );
+            return quote! { compile_error!(#msg) }.into();
+        }
+        Err(msg) => return quote! { compile_error!(#msg) }.into(),
+    };
+
+    let full_path = &theme.full_path;
+    let style = crate::emit_stylesheet(&theme.to_css(), true, "<style>{}</style>");
+    quote! {
+        {
+            const _: &str = include_str!(#full_path);
+            azumi::from_fn(move |f| {
+                #style
+                Ok(())
+            })
+        }
+    }
+    .into()
+}
+
+/// The crate's theme, or None without a theme.toml
+pub fn load_theme() -> Result<Option<Theme>, String> {
+    let Ok((source, full_path)) = crate::read_manifest_file(THEME_FILE) else {
+        return Ok(None);
+    };
+    let tokens = parse_tokens(&source).map_err(|e| format!("{}: {}", THEME_FILE, e))?;
+    Ok(Some(Theme { full_path, tokens }))
+}
+
+fn parse_tokens(source: &str) -> Result<BTreeMap<String, Token>, String> {
+    fn walk(
+        path: &str,
+        property: Option<&'static str>,
+        table: &toml::Table,
+        out: &mut BTreeMap<String, Token>,
+    ) -> Result<(), String> {
+        for (key, value) in table {
+            let name = format!("{}-{}", path, key);
+            let property = property.or_else(|| {
+                TYPED_GROUPS
+                    .iter()
+                    .find(|(group, _)| *group == key)
+                    .map(|(_, property)| *property)
+            });
+            match value {
+                toml::Value::String(light) => {
+                    out.insert(
+                        name,
+                        Token {
+                            light: light.clone(),
+                            dark: None,
+                            property,
+                        },
+                    );
+                }
+                // { light = "...", dark = "..." } is one token; other tables are groups
+                toml::Value::Table(variants) if variants.contains_key("light") => {
+                    let variant = |v: &str| variants.get(v).and_then(|v| v.as_str());
+                    let (Some(light), dark) = (variant("light"), variant("dark")) else {
+                        return Err(format!(
+                            "'{}' light/dark values must be strings",
+                            &name[2..]
+                        ));
+                    };
+                    if let Some(extra) = variants.keys().find(|k| *k != "light" && *k != "dark") {
+                        return Err(format!(
+                            "'{}' has unknown variant '{}' (expected light and dark)",
+                            &name[2..],
+                            extra
+                        ));
+                    }
+                    out.insert(
+                        name,
+                        Token {
+                            light: light.to_string(),
+                            dark: dark.map(str::to_string),
+                            property,
+                        },
+                    );
+                }
+                toml::Value::Table(group) => walk(&name, property, group, out)?,
+                _ => return Err(format!("'{}' must be a string or a table", &name[2..])),
+            }
+        }
+        Ok(())
+    }
+
+    let table: toml::Table = source.parse().map_err(|e| format!("{}", e))?;
+    let mut tokens = BTreeMap::new();
+    walk("-", None, &table, &mut tokens)?;
+
+    for (name, token) in &tokens {
+        for value in std::iter::once(&token.light).chain(&token.dark) {
+            check_type(token, value).map_err(|e| format!("{}: {}", name, e))?;
+        }
+    }
+    Ok(tokens)
+}
+
+// Values of typed groups must be valid for their property (references are resolved by the browser)
+fn check_type(token: &Token, value: &str) -> Result<(), String> {
+    let Some(property) = token.property else {
+        return Ok(());
+    };
+    if value.contains("var(") {
+        return Ok(());
+    }
+    match Property::parse_string(PropertyId::from(property), value, ParserOptions::default()) {
+        Ok(Property::Unparsed(_)) | Err(_) => {
+            Err(format!("'{}' is not a valid {} value", value, property))
+        }
+        Ok(_) => Ok(()),
+    }
+}
+
+impl Theme {
+    fn to_css(&self) -> String {
+        let light: String = self
+            .tokens
+            .iter()
+            .map(|(name, token)| format!("{}: {}; ", name, token.light))
+            .collect();
+        let dark: String = self
+            .tokens
+            .iter()
+            .filter_map(|(name, token)| Some(format!("{}: {}; ", name, token.dark.as_ref()?)))
+            .collect();
+
+        let mut css = format!(":root {{ {}}}", light);
+        if !dark.is_empty() {
+            css.push_str(&format!(
+                " @media {} {{ {} {{ {}}} }} {} {{ {}}}",
+                DARK_PREFERENCE_MEDIA, DARK_PREFERENCE_SELECTOR, dark, DARK_SELECTOR, dark
+            ));
+        }
+        css
+    }
+
+    // `hint` says how the component could provide the variable itself
+    fn unknown(&self, name: &str, hint: &str) -> String {
+        use crate::attribute_value_validator::edit_distance;
+        let similar = self
+            .tokens
+            .keys()
+            .map(|candidate| (edit_distance(name, candidate), candidate))
+            .filter(|(distance, _)| *distance <= 2)
+            .min_by_key(|(distance, _)| *distance);
+        match similar {
+            Some((_, similar)) => format!(
+                "Unknown CSS variable '{}'. Did you mean '{}'?",
+                name, similar
+            ),
+            None => format!(
+                "Unknown CSS variable '{}'. Declare it in {} or {}.",
+                name, THEME_FILE, hint
+            ),
+        }
+    }
+}
+
+/// `--name`s referenced with var() in a CSS value, and whether each var()
+/// has a fallback (`var(--gap, 1rem)`)
+pub fn var_references(value: &str) -> Vec<(String, bool)> {
+    let mut names = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("var(") {
+        rest = rest[start + 4..].trim_start();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+        let name = &rest[..len];
+        rest = &rest[len..];
+        if name.starts_with("--") {
+            names.push((name.to_string(), rest.trim_start().starts_with(',')));
+        }
+    }
+    names
+}
+
+/// Check a component's CSS variables against theme.toml:
+/// var(--x) must be a token, set by the component, or listed in `@inherited(--x)`
+/// when an ancestor sets it (a var() with a fallback is not checked), and inline
+/// `style={ --x: ... }` must set a token (with a value of its type), a variable
+/// its CSS reads, or one listed in `@provides(--x)` for its children.
+/// On success, returns an include_str! of theme.toml so editing it re-runs the checks.
+pub fn validate_css_variables(
+    nodes: &[Node],
+) -> Result<proc_macro2::TokenStream, proc_macro2::TokenStream> {
+    let theme = match load_theme() {
+        Ok(Some(theme)) => theme,
+        Ok(None) => return Ok(quote! {}),
+        Err(msg) => return Err(quote! { compile_error!(#msg); }),
+    };
+
+    let mut vars = ComponentVariables::default();
+    vars.collect(nodes);
+    let read: HashSet<&str> = vars
+        .used
+        .iter()
+        .map(|(name, _)| name)
+        .chain(&vars.with_fallback)
+        .map(String::as_str)
+        .collect();
+    let set: HashSet<&str> = vars
+        .declared
+        .iter()
+        .chain(vars.inline.iter().map(|v| &v.0))
+        .map(String::as_str)
+        .collect();
+
+    let mut errors = Vec::new();
+    for (name, span) in &vars.used {
+        if !theme.tokens.contains_key(name) && !set.contains(name.as_str()) {
+            let hint = format!(
+                "set it in this component, or list it in `@inherited({})` if an ancestor sets it",
+                name
+            );
+            let msg = theme.unknown(name, &hint);
+            errors.push(quote_spanned! { *span => compile_error!(#msg); });
+        }
+    }
+    for (name, value, span) in &vars.inline {
+        let msg = match theme.tokens.get(name) {
+            Some(token) => match value.as_deref().map(|v| check_type(token, v)) {
+                Some(Err(e)) => format!("Invalid value for theme token '{}': {}", name, e),
+                _ => continue,
+            },
+            None if read.contains(name.as_str()) || vars.provided.contains(name) => continue,
+            None => {
+                let hint = format!(
+                    "read it with var() in this component's <style>, or list it in `@provides({})` if a child component reads it",
+                    name
+                );
+                theme.unknown(name, &hint)
+            }
+        };
+        errors.push(quote_spanned! { *span => compile_error!(#msg); });
+    }
+    if !errors.is_empty() {
+        return Err(quote! { #(#errors)* });
+    }
+    let path = &theme.full_path;
+    Ok(quote! { const _: &str = include_str!(#path); })
+}
+
+#[derive(Default)]
+struct ComponentVariables {
+    declared: Vec<String>,      // `--x: "..."` or @inherited(--x) in <style>
+    used: Vec<(String, Span)>,  // var(--x) in <style> and inline styles
+    with_fallback: Vec<String>, // var(--x, fallback), read but not checked
+    provided: Vec<String>,      // @provides(--x) in <style>, set inline for children
+    inline: Vec<(String, Option<String>, Span)>, // style={ --x: value } (value if static)
+}
+
+impl ComponentVariables {
+    fn collect(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            match node {
+                Node::Element(elem) => {
+                    for attr in elem.attrs.iter().filter(|a| a.name == "style") {
+                        let span = attr.value_span.unwrap_or(attr.span);
+                        match &attr.value {
+                            AttributeValue::StyleDsl(props) => {
+                                for (name, value) in props {
+                                    let value = syn::parse2::<syn::LitStr>(value.clone())
+                                        .ok()
+                                        .map(|s| s.value());
+                                    if let Some(value) = &value {
+                                        self.use_all(value, span);
+                                    }
+                                    self.inline.push((name.clone(), value, span));
+                                }
+                            }
+                            AttributeValue::Static(style) => {
+                                for (name, value) in
+                                    style.split(';').filter_map(|d| d.split_once(':'))
+                                {
+                                    self.use_all(value, span);
+                                    let value = Some(value.trim().to_string());
+                                    self.inline.push((name.trim().to_string(), value, span));
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    self.collect(&elem.children);
+                }
+                Node::Fragment(frag) => self.collect(&frag.children),
+                Node::Block(block) => match block {
+                    Block::Style(style) => {
+                        let vars = crate::style::css_variables(style.content.clone());
+                        self.declared.extend(vars.declared);
+                        self.used.extend(vars.used);
+                        self.with_fallback.extend(vars.with_fallback);
+                        self.provided.extend(vars.provided);
+                    }
+                    Block::If(if_block) => {
+                        self.collect(&if_block.then_branch);
+                        if let Some(else_branch) = &if_block.else_branch {
+                            self.collect(else_branch);
+                        }
+                    }
+                    Block::For(for_block) => {
+                        self.collect(&for_block.body);
+                        if let Some(empty_branch) = &for_block.empty_branch {
+                            self.collect(empty_branch);
+                        }
+                    }
+                    Block::Match(match_block) => {
+                        for arm in &match_block.arms {
+                            self.collect(&arm.body);
+                        }
+                    }
+                    Block::Call(call_block) => self.collect(&call_block.children),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn use_all(&mut self, value: &str, span: Span) {
+        for (name, fallback) in var_references(value) {
+            if fallback {
+                self.with_fallback.push(name);
+            } else {
+                self.used.push((name, span));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tokens() {
+        let tokens = parse_tokens(
+            "[color]\nprimary = \"#4f46e5\"\nsurface = { light = \"#fff\", dark = \"#111827\" }\n\
+             [color.brand]\naccent = \"var(--color-primary)\"\n[space]\nmd = \"1rem\"\n",
+        )
+        .unwrap();
+        let names: Vec<&str> = tokens.keys().map(String::as_str).collect();
+        assert_eq!(
+            names,
+            [
+                "--color-brand-accent",
+                "--color-primary",
+                "--color-surface",
+                "--space-md"
+            ]
+        );
+        assert_eq!(tokens["--color-surface"].dark.as_deref(), Some("#111827"));
+    }
+
+    #[test]
+    fn test_typed_groups() {
+        let err = parse_tokens("[color]\nprimary = \"12px\"\n").err().unwrap();
+        assert!(err.contains("--color-primary"));
+        assert!(err.contains("not a valid color value"));
+        assert!(parse_tokens("[space]\nmd = \"red\"\n").is_err());
+        assert!(parse_tokens("[misc]\nanything = \"red 12px\"\n").is_ok());
+    }
+
+    #[test]
+    fn test_theme_css() {
+        let theme = Theme {
+            full_path: String::new(),
+            tokens: parse_tokens("[color]\nbg = { light = \"white\", dark = \"black\" }\n")
+                .unwrap(),
+        };
+        let css = theme.to_css();
+        assert!(css.starts_with(":root { --color-bg: white; }"));
+        assert!(css.contains("@media (prefers-color-scheme: dark) { :root:not([data-theme=light]) { --color-bg: black; } }"));
+        assert!(css.ends_with("[data-theme=dark] { --color-bg: black; }"));
+    }
+
+    #[test]
+    fn test_var_references() {
+        assert_eq!(
+            var_references("calc(var(--space-md) * 2) var( --color-bg , red)"),
+            [
+                ("--space-md".to_string(), false),
+                ("--color-bg".to_string(), true)
+            ]
+        );
+    }
+}
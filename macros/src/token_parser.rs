@@ -2,7 +2,7 @@ use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::{quote, ToTokens};
 use syn::{
     ext::IdentExt,
-    parse::{Parse, ParseStream},
+    parse::{Parse, ParseStream, Parser},
     spanned::Spanned,
     token::{Brace, Paren},
     Error, Ident, Result, Token,
@@ -20,12 +20,51 @@ pub enum Node {
     Block(Block),
 }
 
+/// HTML boolean attributes: no value required, and `{expr}` values are written only when true
+pub const BOOLEAN_ATTRS: &[&str] = &[
+    "disabled",
+    "checked",
+    "selected",
+    "readonly",
+    "required",
+    "autofocus",
+    "autoplay",
+    "controls",
+    "loop",
+    "muted",
+    "default",
+    "formnovalidate",
+    "ismap",
+    "multiple",
+    "nomodule",
+    "novalidate",
+    "open",
+    "reversed",
+];
+
+/// Azumi markers that may also omit their value
+const MARKER_ATTRS: &[&str] = &[
+    "global", // global style tag attribute
+    "az-oob", // out-of-band swap marker
+];
+
+/// Markup namespace of an element (inline SVG / MathML subtrees)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Namespace {
+    #[default]
+    Html,
+    Svg,
+    MathMl,
+}
+
 #[derive(Debug, Clone)]
 pub struct Element {
     pub name: String,
+    pub namespace: Namespace,
     pub attrs: Vec<Attribute>,
     pub children: Vec<Node>,
     pub bind_struct: Option<syn::Path>,
+    pub spreads: Vec<TokenStream>, // {..attrs} expressions, written after attrs
     pub span: Span,
 }
 
@@ -79,6 +118,7 @@ pub struct Doctype {
 #[derive(Debug, Clone)]
 pub struct Fragment {
     pub children: Vec<Node>,
+    pub name: Option<String>, // <fragment name="row">: renderable on its own
     #[allow(dead_code)]
     pub span: Span,
 }
@@ -96,9 +136,9 @@ pub enum Block {
 
 #[derive(Debug, Clone)]
 pub struct IfBlock {
-    pub condition: TokenStream,
+    pub condition: TokenStream, // Also holds `let Some(x) = expr` for if-let
     pub then_branch: Vec<Node>,
-    pub else_branch: Option<Vec<Node>>,
+    pub else_branch: Option<Vec<Node>>, // `else if` nests a single @if here
     #[allow(dead_code)]
     pub span: Span,
 }
@@ -108,6 +148,8 @@ pub struct ForBlock {
     pub pattern: TokenStream,
     pub iterator: TokenStream,
     pub body: Vec<Node>,
+    pub empty_branch: Option<Vec<Node>>, // @empty { ... } when the loop ran zero times
+    pub loop_context: bool, // `@for (item, loop) in items` exposes azumi::LoopContext as `loop`
     #[allow(dead_code)]
     pub span: Span,
 }
@@ -258,6 +300,10 @@ pub fn parse_nodes(input: ParseStream) -> Result<Vec<Node>> {
                         nodes.push(parse_style_tag(input)?);
                         continue;
                     }
+                    if name == "fragment" {
+                        nodes.push(Node::Fragment(parse_named_fragment(input)?));
+                        continue;
+                    }
                 }
                 nodes.push(Node::Element(input.parse()?));
             }
@@ -266,6 +312,8 @@ pub fn parse_nodes(input: ParseStream) -> Result<Vec<Node>> {
                 // @{ ... } -> Expression
                 input.parse::<Token![@]>()?;
                 nodes.push(Node::Expression(input.parse()?));
+            } else if peek_markdown(input) {
+                nodes.push(Node::Fragment(parse_markdown_block(input)?));
             } else {
                 // Block
                 nodes.push(Node::Block(input.parse()?));
@@ -298,12 +346,27 @@ impl Parse for Element {
         let start_span = input.span();
         input.parse::<Token![<]>()?;
         let (name, name_span) = parse_html_name(input, false)?; // false = don't allow double dash in tag names
-        eprintln!("Parsing element: {}", name);
 
         let mut attrs = Vec::new();
         let mut bind_struct = None;
+        let mut spreads = Vec::new();
 
         while !input.peek(Token![>]) && !input.peek(Token![/]) {
+            // Spread attributes: {..attrs}
+            if input.peek(Brace) {
+                let content;
+                syn::braced!(content in input);
+                if !content.peek(Token![..]) {
+                    return Err(Error::new(
+                        content.span(),
+                        "Expected attribute spread {..attrs} inside a tag. Dynamic attribute values are written as name={expr}.",
+                    ));
+                }
+                content.parse::<Token![..]>()?;
+                spreads.push(content.parse()?);
+                continue;
+            }
+
             // Check for bind={Struct} on <form> tags
             if name == "form" && input.peek(Ident) {
                 let fork = input.fork();
@@ -409,14 +472,9 @@ For dynamic styles: use style attribute with expressions"
 
                 // Expect closing tag
                 if input.peek(Token![<]) && input.peek2(Token![/]) {
-                    eprintln!("Element::parse: Found closing tag sequence");
                     input.parse::<Token![<]>()?;
                     input.parse::<Token![/]>()?;
                     let (closing_name, _) = parse_html_name(input, false)?;
-                    eprintln!(
-                        "Found closing tag: </{}>  (expected </{}>)",
-                        closing_name, name
-                    );
                     if closing_name != name {
                         return Err(Error::new(
                             input.span(),
@@ -436,11 +494,23 @@ For dynamic styles: use style attribute with expressions"
             }
         }
 
+        // <svg> and <math> start a foreign subtree; names inside are case-sensitive
+        let namespace = match name.as_str() {
+            "svg" => Namespace::Svg,
+            "math" => Namespace::MathMl,
+            _ => Namespace::Html,
+        };
+        if namespace != Namespace::Html {
+            assign_namespace(&mut children, namespace);
+        }
+
         Ok(Element {
             name,
+            namespace,
             attrs,
             children,
             bind_struct,
+            spreads,
             span: start_span,
         })
     }
@@ -450,29 +520,6 @@ impl Parse for Attribute {
     fn parse(input: ParseStream) -> Result<Self> {
         let (name, name_span) = parse_html_name(input, true)?; // true = allow double dash in attributes
 
-        // Check if this is a boolean attribute (no value required)
-        const BOOLEAN_ATTRS: &[&str] = &[
-            "disabled",
-            "checked",
-            "selected",
-            "readonly",
-            "required",
-            "autofocus",
-            "autoplay",
-            "controls",
-            "loop",
-            "muted",
-            "default",
-            "formnovalidate",
-            "ismap",
-            "multiple",
-            "nomodule",
-            "novalidate",
-            "open",
-            "reversed",
-            "global", // Azumi: global style tag attribute
-        ];
-
         let (value, value_span) = if input.peek(Token![=]) {
             input.parse::<Token![=]>()?;
             if input.peek(Brace) {
@@ -568,7 +615,7 @@ impl Parse for Attribute {
                 ));
             }
 
-            if !BOOLEAN_ATTRS.contains(&name.as_str()) {
+            if !BOOLEAN_ATTRS.contains(&name.as_str()) && !MARKER_ATTRS.contains(&name.as_str()) {
                 return Err(Error::new(
                     name_span,
                     format!("Attribute '{}' requires a value. Use {}=\"value\" or {}={{expr}}.\nOnly boolean attributes like 'disabled', 'checked', etc. can omit values.", name, name, name)
@@ -805,6 +852,48 @@ fn parse_html_name(input: ParseStream, allow_double_dash: bool) -> Result<(Strin
     Ok((name, full_span))
 }
 
+/// Mark descendants of <svg>/<math> with the subtree's namespace.
+/// Nested <svg>/<math> roots already marked their own subtree, and
+/// <foreignObject> children go back to HTML.
+fn assign_namespace(nodes: &mut [Node], namespace: Namespace) {
+    for node in nodes {
+        match node {
+            Node::Element(elem) => {
+                if elem.name == "svg" || elem.name == "math" {
+                    continue;
+                }
+                elem.namespace = namespace;
+                if elem.name != "foreignObject" {
+                    assign_namespace(&mut elem.children, namespace);
+                }
+            }
+            Node::Fragment(frag) => assign_namespace(&mut frag.children, namespace),
+            Node::Block(block) => match block {
+                Block::If(if_block) => {
+                    assign_namespace(&mut if_block.then_branch, namespace);
+                    if let Some(else_branch) = &mut if_block.else_branch {
+                        assign_namespace(else_branch, namespace);
+                    }
+                }
+                Block::For(for_block) => {
+                    assign_namespace(&mut for_block.body, namespace);
+                    if let Some(empty_branch) = &mut for_block.empty_branch {
+                        assign_namespace(empty_branch, namespace);
+                    }
+                }
+                Block::Match(match_block) => {
+                    for arm in &mut match_block.arms {
+                        assign_namespace(&mut arm.body, namespace);
+                    }
+                }
+                Block::Call(call_block) => assign_namespace(&mut call_block.children, namespace),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
 fn is_void_element(name: &str) -> bool {
     matches!(
         name,
@@ -853,7 +942,6 @@ fn is_css_at_rule(input: ParseStream) -> bool {
 
 fn parse_script_content(input: ParseStream, tag_name: &str) -> Result<Vec<Node>> {
     let mut nodes = Vec::new();
-    eprintln!("parse_script_content starting for tag: {}", tag_name);
     while !input.is_empty() {
         if input.peek(Token![<]) && input.peek2(Token![/]) {
             let fork = input.fork();
@@ -861,7 +949,6 @@ fn parse_script_content(input: ParseStream, tag_name: &str) -> Result<Vec<Node>>
             fork.parse::<Token![/]>()?;
             if let Ok((name, _)) = parse_html_name(&fork, false) {
                 if name == tag_name {
-                    eprintln!("Found closing tag: </{}>", name);
                     break;
                 }
             }
@@ -869,20 +956,14 @@ fn parse_script_content(input: ParseStream, tag_name: &str) -> Result<Vec<Node>>
 
         if input.peek(Token![@]) {
             let is_css = is_css_at_rule(input);
-            eprintln!("Found @ in script, is_css_at_rule: {}", is_css);
             if !is_css {
-                eprintln!("Not CSS, checking if it's a Brace...");
                 if input.peek2(Brace) {
                     // @{ ... } -> Expression
-                    eprintln!("Found @{{ ... }} expression!");
                     input.parse::<Token![@]>()?;
                     nodes.push(Node::Expression(input.parse()?));
                 } else {
-                    eprintln!("Found Block (not brace)");
                     nodes.push(Node::Block(input.parse()?));
                 }
-            } else {
-                eprintln!("IS CSS, treating as text");
             }
         }
 
@@ -890,10 +971,8 @@ fn parse_script_content(input: ParseStream, tag_name: &str) -> Result<Vec<Node>>
             // Parse as text until @ (if not CSS) or </tag_name>
             let span = input.span();
             let mut tokens = Vec::new();
-            eprintln!("Parsing text...");
             while !input.is_empty() {
                 if input.peek(Token![@]) && !is_css_at_rule(input) {
-                    eprintln!("Stopped text at @");
                     break;
                 }
                 if input.peek(Token![<]) && input.peek2(Token![/]) {
@@ -902,20 +981,17 @@ fn parse_script_content(input: ParseStream, tag_name: &str) -> Result<Vec<Node>>
                     fork.parse::<Token![/]>()?;
                     if let Ok((name, _)) = parse_html_name(&fork, false) {
                         if name == tag_name {
-                            eprintln!("Stopped text at closing tag");
                             break;
                         }
                     }
                 }
 
                 let tt: TokenTree = input.parse()?;
-                eprintln!("Consumed token: {:?}", tt);
                 tokens.push(tt);
             }
 
             if !tokens.is_empty() {
                 let content = tokens_to_string(&tokens);
-                eprintln!("Created Text node: {:?}", content);
                 nodes.push(Node::Text(Text { content, span }));
             }
         }
@@ -975,9 +1051,15 @@ impl Parse for IfBlock {
 
         let else_branch = if input.peek(Token![else]) {
             input.parse::<Token![else]>()?;
-            let content;
-            syn::braced!(content in input);
-            Some(parse_nodes(&content)?)
+            if input.peek(Token![if]) {
+                // else if: nest the rest of the chain as a single @if
+                let nested: IfBlock = input.parse()?;
+                Some(vec![Node::Block(Block::If(nested))])
+            } else {
+                let content;
+                syn::braced!(content in input);
+                Some(parse_nodes(&content)?)
+            }
         } else {
             None
         };
@@ -1022,19 +1104,142 @@ impl Parse for ForBlock {
                 "Expected block { ... } in for loop",
             ));
         }
+        // `(pattern, loop)`: strip the loop binding and remember to generate it
+        let (pattern, loop_context) = match split_loop_context(&pre_in) {
+            Some(item) => (item, true),
+            None => (pre_in, false),
+        };
+
         let content;
         syn::braced!(content in input);
-        let body = parse_nodes(&content)?;
+        let body = if loop_context {
+            let tokens = rename_loop_refs(content.parse::<TokenStream>()?);
+            parse_nodes.parse2(tokens)?
+        } else {
+            parse_nodes(&content)?
+        };
+
+        // Optional @empty { ... } right after the loop body
+        let empty_branch = if peek_empty(input) {
+            input.parse::<Token![@]>()?;
+            input.parse::<Ident>()?;
+            let content;
+            syn::braced!(content in input);
+            Some(parse_nodes(&content)?)
+        } else {
+            None
+        };
 
         Ok(ForBlock {
-            pattern: pre_in,
+            pattern,
             iterator,
             body,
+            empty_branch,
+            loop_context,
             span,
         })
     }
 }
 
+/// Name the loop context is bound to in generated code (`loop` is a keyword)
+pub const LOOP_VAR: &str = "__azumi_loop";
+
+/// Split `(pattern, loop)` into `pattern`, or None if there is no loop binding
+fn split_loop_context(pre_in: &TokenStream) -> Option<TokenStream> {
+    let mut tokens = pre_in.clone().into_iter();
+    let (Some(TokenTree::Group(group)), None) = (tokens.next(), tokens.next()) else {
+        return None;
+    };
+    if group.delimiter() != proc_macro2::Delimiter::Parenthesis {
+        return None;
+    }
+
+    let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+    let is_loop = matches!(inner.last(), Some(TokenTree::Ident(i)) if i == "loop");
+    let has_comma =
+        matches!(inner.iter().rev().nth(1), Some(TokenTree::Punct(p)) if p.as_char() == ',');
+    if !is_loop || !has_comma {
+        return None;
+    }
+    Some(inner[..inner.len() - 2].iter().cloned().collect())
+}
+
+/// Rewrite `loop.field` to `__azumi_loop.field` (nested loops shadow naturally)
+fn rename_loop_refs(tokens: TokenStream) -> TokenStream {
+    let mut out = Vec::new();
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(tt) = iter.next() {
+        match tt {
+            TokenTree::Ident(ident)
+                if ident == "loop"
+                    && matches!(iter.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '.') =>
+            {
+                out.push(TokenTree::Ident(Ident::new(LOOP_VAR, ident.span())));
+            }
+            TokenTree::Group(group) => {
+                let mut renamed =
+                    proc_macro2::Group::new(group.delimiter(), rename_loop_refs(group.stream()));
+                renamed.set_span(group.span());
+                out.push(TokenTree::Group(renamed));
+            }
+            other => out.push(other),
+        }
+    }
+    out.into_iter().collect()
+}
+
+fn peek_empty(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<Token![@]>().is_ok()
+        && fork.parse::<Ident>().map(|i| i == "empty").unwrap_or(false)
+        && fork.peek(Brace)
+}
+
+fn peek_markdown(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<Token![@]>().is_ok()
+        && fork
+            .parse::<Ident>()
+            .map(|i| i == "markdown")
+            .unwrap_or(false)
+        && fork.peek(Brace)
+}
+
+/// @markdown { "# Title" @Counter(start = 1) "More *text*" }
+/// String literals are Markdown; elements, blocks and {expr} between them are embedded as-is.
+fn parse_markdown_block(input: ParseStream) -> Result<Fragment> {
+    input.parse::<Token![@]>()?;
+    let ident: Ident = input.parse()?;
+    let content;
+    syn::braced!(content in input);
+
+    let mut children = Vec::new();
+    while !content.is_empty() {
+        if content.peek(syn::LitStr) {
+            let lit: syn::LitStr = content.parse()?;
+            let nodes = crate::markdown::markdown_to_nodes(&lit.value(), lit.span())
+                .map_err(|msg| Error::new(lit.span(), msg))?;
+            children.extend(nodes);
+        } else if content.peek(Token![@]) {
+            children.push(Node::Block(content.parse()?));
+        } else if content.peek(Token![<]) {
+            children.push(Node::Element(content.parse()?));
+        } else if content.peek(Brace) {
+            children.push(Node::Expression(content.parse()?));
+        } else {
+            return Err(content.error(
+                "Expected a Markdown string literal, an element, @Component(...) or {expr}",
+            ));
+        }
+    }
+
+    Ok(Fragment {
+        children,
+        name: None,
+        span: ident.span(),
+    })
+}
+
 impl Parse for LetBlock {
     fn parse(input: ParseStream) -> Result<Self> {
         let span = input.span();
@@ -1201,8 +1406,73 @@ impl Parse for Fragment {
         input.parse::<Token![/]>()?;
         input.parse::<Token![>]>()?;
 
-        Ok(Fragment { children, span })
+        Ok(Fragment {
+            children,
+            name: None,
+            span,
+        })
+    }
+}
+
+/// <fragment name="row"> ... </fragment>
+/// Renders its children inline; azumi::render_fragment can render it alone.
+fn parse_named_fragment(input: ParseStream) -> Result<Fragment> {
+    let span = input.span();
+    input.parse::<Token![<]>()?;
+    parse_html_name(input, false)?; // "fragment"
+
+    let mut name = None;
+    while !input.peek(Token![>]) {
+        let attr: Attribute = input.parse()?;
+        match (attr.name.as_str(), attr.value) {
+            ("name", AttributeValue::Static(value)) if !value.is_empty() => name = Some(value),
+            ("name", _) => {
+                return Err(Error::new(
+                    attr.span,
+                    "Fragment name must be a non-empty string literal: <fragment name=\"row\">",
+                ))
+            }
+            (other, _) => {
+                return Err(Error::new(
+                    attr.span,
+                    format!(
+                        "<fragment> only accepts a name attribute, found '{}'",
+                        other
+                    ),
+                ))
+            }
+        }
+    }
+    input.parse::<Token![>]>()?;
+
+    let Some(name) = name else {
+        return Err(Error::new(
+            span,
+            "<fragment> needs a name: <fragment name=\"row\">. Use <> ... </> for an unnamed fragment.",
+        ));
+    };
+
+    let children = parse_nodes(input)?;
+
+    input.parse::<Token![<]>()?;
+    input.parse::<Token![/]>()?;
+    let (closing_name, closing_span) = parse_html_name(input, false)?;
+    if closing_name != "fragment" {
+        return Err(Error::new(
+            closing_span,
+            format!(
+                "Mismatched closing tag: expected </fragment>, found </{}>",
+                closing_name
+            ),
+        ));
     }
+    input.parse::<Token![>]>()?;
+
+    Ok(Fragment {
+        children,
+        name: Some(name),
+        span,
+    })
 }
 
 pub struct HtmlInput {
@@ -54,3 +54,149 @@ fn test_namespaced_attributes() {
         Err(e) => panic!("Parse failed: {}", e),
     }
 }
+
+#[test]
+fn test_oob_marker_is_boolean() {
+    let input = quote! {
+        <span id={badge} az-oob>"3"</span>
+    };
+    let parser = parse_nodes_wrapper;
+    let nodes = parser
+        .parse2(input)
+        .expect("az-oob should parse without a value");
+    if let Node::Element(elem) = &nodes[0] {
+        assert_eq!(elem.attrs[1].name, "az-oob");
+    } else {
+        panic!("Expected Element node");
+    }
+}
+
+#[test]
+fn test_else_if_chain_nests() {
+    use crate::token_parser::Block;
+    let input = quote! {
+        @if a { "a" } else if b { "b" } else { "c" }
+    };
+    let parser = parse_nodes_wrapper;
+    let nodes = parser.parse2(input).expect("else if chain should parse");
+    let Node::Block(Block::If(outer)) = &nodes[0] else {
+        panic!("Expected @if block");
+    };
+    let else_branch = outer.else_branch.as_ref().expect("else branch");
+    let Node::Block(Block::If(inner)) = &else_branch[0] else {
+        panic!("Expected nested @if for else if");
+    };
+    assert_eq!(inner.condition.to_string(), "b");
+    assert!(inner.else_branch.is_some());
+}
+
+#[test]
+fn test_for_empty_branch() {
+    use crate::token_parser::Block;
+    let input = quote! {
+        @for item in items { <li>{item}</li> } @empty { <p>"Nothing yet"</p> }
+    };
+    let parser = parse_nodes_wrapper;
+    let nodes = parser.parse2(input).expect("@empty should parse");
+    assert_eq!(nodes.len(), 1);
+    let Node::Block(Block::For(for_block)) = &nodes[0] else {
+        panic!("Expected @for block");
+    };
+    assert!(for_block.empty_branch.is_some());
+}
+
+#[test]
+fn test_spread_attributes() {
+    let input = quote! {
+        <input type="text" {..attrs} />
+    };
+    let parser = parse_nodes_wrapper;
+    let nodes = parser.parse2(input).expect("spread should parse");
+    let Node::Element(elem) = &nodes[0] else {
+        panic!("Expected Element node");
+    };
+    assert_eq!(elem.attrs.len(), 1);
+    assert_eq!(elem.spreads.len(), 1);
+    assert_eq!(elem.spreads[0].to_string(), "attrs");
+
+    let bad = parser.parse2(quote! { <input {attrs} /> });
+    assert!(bad.is_err());
+}
+
+#[test]
+fn test_collapse_whitespace() {
+    assert_eq!(
+        crate::collapse_whitespace("  Hello \n\t world  "),
+        " Hello world "
+    );
+    assert_eq!(crate::collapse_whitespace("\n    "), " ");
+    assert_eq!(crate::collapse_whitespace("a"), "a");
+}
+
+#[test]
+fn test_svg_and_mathml_namespaces() {
+    use crate::token_parser::Namespace;
+    let input = quote! {
+        <div>
+            <svg viewBox="0 0 24 24">
+                <clipPath id={clip}><rect width="24" height="24" /></clipPath>
+                <foreignObject><p>"html"</p></foreignObject>
+            </svg>
+            <math><mi>"x"</mi></math>
+        </div>
+    };
+    let parser = parse_nodes_wrapper;
+    let nodes = parser.parse2(input).expect("svg should parse");
+    let Node::Element(div) = &nodes[0] else {
+        panic!("Expected Element node");
+    };
+    assert_eq!(div.namespace, Namespace::Html);
+
+    let Node::Element(svg) = &div.children[0] else {
+        panic!("Expected <svg>");
+    };
+    assert_eq!(svg.namespace, Namespace::Svg);
+    assert_eq!(svg.attrs[0].name, "viewBox");
+    let Node::Element(clip) = &svg.children[0] else {
+        panic!("Expected <clipPath>");
+    };
+    assert_eq!(clip.name, "clipPath");
+    assert_eq!(clip.namespace, Namespace::Svg);
+    let Node::Element(foreign) = &svg.children[1] else {
+        panic!("Expected <foreignObject>");
+    };
+    let Node::Element(p) = &foreign.children[0] else {
+        panic!("Expected <p>");
+    };
+    assert_eq!(p.namespace, Namespace::Html);
+
+    let Node::Element(math) = &div.children[1] else {
+        panic!("Expected <math>");
+    };
+    let Node::Element(mi) = &math.children[0] else {
+        panic!("Expected <mi>");
+    };
+    assert_eq!(mi.namespace, Namespace::MathMl);
+}
+
+#[test]
+fn test_named_fragment() {
+    let input = quote! {
+        <ul><fragment name="row"><li>"a"</li></fragment></ul>
+    };
+    let parser = parse_nodes_wrapper;
+    let nodes = parser.parse2(input).expect("named fragment should parse");
+    let Node::Element(ul) = &nodes[0] else {
+        panic!("Expected Element node");
+    };
+    let Node::Fragment(fragment) = &ul.children[0] else {
+        panic!("Expected Fragment node");
+    };
+    assert_eq!(fragment.name.as_deref(), Some("row"));
+    assert_eq!(fragment.children.len(), 1);
+
+    let missing = parser.parse2(quote! { <fragment><li>"a"</li></fragment> });
+    assert!(missing.unwrap_err().to_string().contains("needs a name"));
+    let mismatched = parser.parse2(quote! { <fragment name="row"></div> });
+    assert!(mismatched.is_err());
+}
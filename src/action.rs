@@ -20,9 +20,28 @@ pub struct ActionEntry {
 inventory::collect!(ActionEntry);
 
 /// Register all collected actions into the router
-pub fn register_actions(mut router: axum::Router) -> axum::Router {
+pub fn register_actions(router: axum::Router) -> axum::Router {
+    register_actions_with(router, |_path, route| route)
+}
+
+/// Register all collected actions, passing each route through `hook` first.
+/// The hook receives the action path so layers can be applied selectively:
+///
+/// ```ignore
+/// azumi::action::register_actions_with(router, |path, route| {
+///     if path.starts_with("/_azumi/action/Admin") {
+///         route.layer(axum::middleware::from_fn(require_admin))
+///     } else {
+///         route
+///     }
+/// })
+/// ```
+pub fn register_actions_with<F>(mut router: axum::Router, hook: F) -> axum::Router
+where
+    F: Fn(&'static str, MethodRouter<()>) -> MethodRouter<()>,
+{
     for entry in inventory::iter::<ActionEntry> {
-        router = router.route(entry.path, (entry.handler)());
+        router = router.route(entry.path, hook(entry.path, (entry.handler)()));
     }
     router
 }
@@ -31,3 +50,295 @@ pub fn register_actions(mut router: axum::Router) -> axum::Router {
 pub async fn handle_action_result<C: Component + ?Sized>(component: &C) -> impl IntoResponse {
     crate::render_to_string(component)
 }
+
+// ============================================================================
+// Response Directives
+// ============================================================================
+
+/// Response headers understood by the client runtime (azumi.js)
+pub mod headers {
+    pub const REDIRECT: &str = "az-redirect";
+    pub const REFRESH: &str = "az-refresh";
+    pub const RETARGET: &str = "az-retarget";
+    pub const SWAP: &str = "az-swap";
+    pub const TRANSITION: &str = "az-transition";
+    pub const PUSH_URL: &str = "az-push-url";
+    pub const TRIGGER: &str = "az-trigger";
+}
+
+/// How the client puts response HTML into the target element
+///
+/// Matches the swap token in `az-on={click call save -> #list beforeend}`
+/// and the value of `az-oob="..."` on out-of-band fragments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Swap {
+    /// Morph the target's children with Idiomorph (default, keeps focus and state)
+    #[default]
+    Morph,
+    /// Replace the target's children
+    InnerHtml,
+    /// Replace the target itself
+    OuterHtml,
+    /// Insert before the target
+    BeforeBegin,
+    /// Insert as the target's first child
+    AfterBegin,
+    /// Insert as the target's last child
+    BeforeEnd,
+    /// Insert after the target
+    AfterEnd,
+    /// Remove the target and ignore the response
+    Delete,
+    /// Leave the DOM alone (directives and triggers still run)
+    None,
+}
+
+impl Swap {
+    /// Token used in the az-on DSL and the `az-swap` header
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Swap::Morph => "morph",
+            Swap::InnerHtml => "innerHTML",
+            Swap::OuterHtml => "outerHTML",
+            Swap::BeforeBegin => "beforebegin",
+            Swap::AfterBegin => "afterbegin",
+            Swap::BeforeEnd => "beforeend",
+            Swap::AfterEnd => "afterend",
+            Swap::Delete => "delete",
+            Swap::None => "none",
+        }
+    }
+}
+
+impl std::fmt::Display for Swap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Typed action response: rendered HTML plus client directives
+///
+/// ```ignore
+/// #[azumi::action]
+/// pub async fn save(form: Form) -> ActionResponse {
+///     ActionResponse::new(&saved_view(&form))
+///         .retarget("#flash")
+///         .toast("Saved!")
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ActionResponse {
+    html: String,
+    redirect: Option<String>,
+    refresh: bool,
+    retarget: Option<String>,
+    swap: Option<Swap>,
+    transition: bool,
+    push_url: Option<String>,
+    triggers: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ActionResponse {
+    /// Render a component as the response body
+    pub fn new<C: Component + ?Sized>(component: &C) -> Self {
+        Self {
+            html: crate::render_to_string(component),
+            ..Self::default()
+        }
+    }
+
+    /// Render only the `<fragment name="...">` parts of a component
+    /// (empty body if the component has no such fragment)
+    pub fn fragment<C: Component + ?Sized>(component: &C, name: &str) -> Self {
+        Self {
+            html: crate::render_fragment(component, name).unwrap_or_default(),
+            ..Self::default()
+        }
+    }
+
+    /// Response with no body (e.g. only directives)
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Append an out-of-band fragment. Its root element must carry `az-oob`
+    /// and an `id`; the client morphs it into the element with that id.
+    ///
+    /// ```ignore
+    /// ActionResponse::new(&cart_list(&cart)).oob(&html! {
+    ///     <span id={cart_badge} az-oob>{cart.len()}</span>
+    /// })
+    /// ```
+    pub fn oob<C: Component + ?Sized>(mut self, component: &C) -> Self {
+        self.html.push_str(&crate::render_to_string(component));
+        self
+    }
+
+    /// Navigate the browser to `url` instead of swapping
+    pub fn redirect(mut self, url: impl Into<String>) -> Self {
+        self.redirect = Some(url.into());
+        self
+    }
+
+    /// Reload the current page instead of swapping
+    pub fn refresh(mut self) -> Self {
+        self.refresh = true;
+        self
+    }
+
+    /// Swap into `selector` instead of the `-> #target` from az-on
+    pub fn retarget(mut self, selector: impl Into<String>) -> Self {
+        self.retarget = Some(selector.into());
+        self
+    }
+
+    /// Override the swap mode from az-on
+    pub fn swap(mut self, mode: Swap) -> Self {
+        self.swap = Some(mode);
+        self
+    }
+
+    /// Wrap the swap in a View Transition (`document.startViewTransition`)
+    /// where the browser supports it
+    pub fn transition(mut self) -> Self {
+        self.transition = true;
+        self
+    }
+
+    /// Push `url` onto the browser history after swapping
+    pub fn push_url(mut self, url: impl Into<String>) -> Self {
+        self.push_url = Some(url.into());
+        self
+    }
+
+    /// Dispatch a DOM `CustomEvent` named `event` on the swap target after swapping
+    pub fn trigger(self, event: impl Into<String>) -> Self {
+        self.trigger_with(event, serde_json::Value::Null)
+    }
+
+    /// Like `trigger`, with `detail` serialized into the event's `detail`
+    pub fn trigger_with<T: serde::Serialize>(
+        mut self,
+        event: impl Into<String>,
+        detail: T,
+    ) -> Self {
+        let detail = serde_json::to_value(detail).unwrap_or(serde_json::Value::Null);
+        self.triggers.insert(event.into(), detail);
+        self
+    }
+
+    /// Shorthand for `trigger_with("azumi:toast", { "message": ... })`
+    pub fn toast(self, message: impl Into<String>) -> Self {
+        self.trigger_with(
+            "azumi:toast",
+            serde_json::json!({ "message": message.into() }),
+        )
+    }
+}
+
+impl IntoResponse for ActionResponse {
+    fn into_response(self) -> axum::response::Response {
+        use axum::http::HeaderValue;
+
+        let mut res = axum::response::Html(self.html).into_response();
+        let h = res.headers_mut();
+        // Values are escaped to visible ASCII below; one that still isn't a valid
+        // header value is left out rather than failing the response
+        let mut set = |name: &'static str, value: &str| {
+            if let Ok(value) = HeaderValue::from_str(value) {
+                h.insert(name, value);
+            }
+        };
+
+        if let Some(url) = &self.redirect {
+            set(headers::REDIRECT, &encode_url(url));
+        }
+        if self.refresh {
+            set(headers::REFRESH, "true");
+        }
+        if let Some(selector) = &self.retarget {
+            set(headers::RETARGET, &escape_selector(selector));
+        }
+        if let Some(mode) = self.swap {
+            set(headers::SWAP, mode.as_str());
+        }
+        if self.transition {
+            set(headers::TRANSITION, "true");
+        }
+        if let Some(url) = &self.push_url {
+            set(headers::PUSH_URL, &encode_url(url));
+        }
+        if !self.triggers.is_empty() {
+            set(
+                headers::TRIGGER,
+                &ascii_json(&serde_json::Value::Object(self.triggers).to_string()),
+            );
+        }
+
+        res
+    }
+}
+
+/// Percent-encode the bytes of `url` that can't go in a header (non-ASCII,
+/// controls, spaces); browsers decode them back when navigating
+fn encode_url(url: &str) -> String {
+    let mut out = String::with_capacity(url.len());
+    for byte in url.bytes() {
+        if byte.is_ascii_graphic() {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// CSS-escape the characters of `selector` that can't go in a header
+/// (`\e9 ` is `é`), which `querySelector` reads back unchanged
+fn escape_selector(selector: &str) -> String {
+    let mut out = String::with_capacity(selector.len());
+    for c in selector.chars() {
+        if c == ' ' || c.is_ascii_graphic() {
+            out.push(c);
+        } else {
+            out.push_str(&format!("\\{:x} ", c as u32));
+        }
+    }
+    out
+}
+
+/// Escape non-ASCII characters and DEL in serialized JSON as `\uXXXX`
+/// (serde_json writes them raw); they only occur inside strings, where the
+/// escape is valid
+fn ascii_json(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c == ' ' || c.is_ascii_graphic() {
+            out.push(c);
+        } else {
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                out.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    out
+}
+
+/// Conversion used by generated action handlers.
+/// Components are rendered to HTML; `ActionResponse` keeps its directives.
+pub trait IntoActionResponse {
+    fn into_action_response(self) -> axum::response::Response;
+}
+
+impl<T: Component> IntoActionResponse for T {
+    fn into_action_response(self) -> axum::response::Response {
+        crate::render_to_string(&self).into_response()
+    }
+}
+
+impl IntoActionResponse for ActionResponse {
+    fn into_action_response(self) -> axum::response::Response {
+        self.into_response()
+    }
+}
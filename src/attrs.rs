@@ -0,0 +1,151 @@
+//! Runtime support for conditional, class-list and spread attributes
+
+use crate::Escaped;
+use std::fmt;
+
+/// Ordered attribute map for wrapper components
+///
+/// ```ignore
+/// #[azumi::component]
+/// fn Button(attrs: Attributes, children: impl Component) -> impl Component {
+///     html! { <button class="btn" {..attrs}>{children}</button> }
+/// }
+///
+/// @Button(attrs = Attributes::new().set("type", "submit").flag("disabled", busy)) { "Save" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attributes {
+    entries: Vec<(String, Option<String>)>,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `name="value"`, replacing an earlier value for the same name
+    pub fn set(mut self, name: impl Into<String>, value: impl fmt::Display) -> Self {
+        self.insert(name.into(), Some(value.to_string()));
+        self
+    }
+
+    /// Set `name="value"` only when `value` is `Some`
+    pub fn set_opt<V: fmt::Display>(self, name: impl Into<String>, value: Option<V>) -> Self {
+        match value {
+            Some(value) => self.set(name, value),
+            None => self,
+        }
+    }
+
+    /// Add a boolean attribute (`disabled`, `required`, ...) when `on` is true
+    pub fn flag(mut self, name: impl Into<String>, on: bool) -> Self {
+        let name = name.into();
+        if on {
+            self.insert(name, None);
+        } else {
+            self.entries.retain(|(n, _)| *n != name);
+        }
+        self
+    }
+
+    /// Value of `name`; `Some("")` for boolean attributes
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_deref().unwrap_or(""))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.entries.iter().map(|(n, v)| (n.as_str(), v.as_deref()))
+    }
+
+    fn insert(&mut self, name: String, value: Option<String>) {
+        match self.entries.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((name, value)),
+        }
+    }
+
+    /// Used by `{..attrs}` in html!. Names that are not valid attribute names are skipped.
+    pub fn write_to(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in self.iter().filter(|(n, _)| is_valid_name(n)) {
+            match value {
+                Some(value) => write!(f, " {}=\"{}\"", name, Escaped(value))?,
+                None => write!(f, " {}", name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+}
+
+/// Join the class names whose condition is true
+/// Usage: html! { <li class={[("item", true), ("active", is_active)]}> }
+pub fn class_list(classes: &[(&str, bool)]) -> String {
+    classes
+        .iter()
+        .filter(|(_, on)| *on)
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Conditional attribute machinery
+// Option values are omitted when None; everything else is written with Display
+
+pub struct AttrWrapper<T>(pub T);
+
+impl<T: fmt::Display> AttrWrapper<&Option<T>> {
+    // Priority 1: Option (omit when None)
+    pub fn write_attr(&self, f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, " {}=\"{}\"", name, Escaped(value)),
+            None => Ok(()),
+        }
+    }
+}
+
+pub trait FallbackAttr {
+    fn write_attr(&self, f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result;
+}
+
+// Priority 2: Display (always written, escaped)
+impl<T: fmt::Display> FallbackAttr for AttrWrapper<&T> {
+    fn write_attr(&self, f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+        write!(f, " {}=\"{}\"", name, Escaped(self.0))
+    }
+}
+
+/// Values accepted by boolean attributes like `disabled={...}`
+pub trait BoolAttr {
+    fn is_on(&self) -> bool;
+}
+
+impl BoolAttr for bool {
+    fn is_on(&self) -> bool {
+        *self
+    }
+}
+
+impl BoolAttr for Option<bool> {
+    fn is_on(&self) -> bool {
+        self.unwrap_or(false)
+    }
+}
+
+impl<T: BoolAttr + ?Sized> BoolAttr for &T {
+    fn is_on(&self) -> bool {
+        (**self).is_on()
+    }
+}
@@ -855,6 +855,9 @@ var Idiomorph = (function () {
  * - Event delegation for az-on attributes
  * - DOM morphing via Idiomorph
  * - Optimistic UI via data-predict attributes (Azumi Live)
+ * - CSRF token header on every action request
+ * - Out-of-band swaps for [az-oob] fragments in action responses
+ * - Swap modes (morph, innerHTML, outerHTML, beforeend, ...) with optional View Transitions
  */
 class Azumi {
     constructor() {
@@ -934,7 +937,7 @@ class Azumi {
     }
 
     parseAction(cmd, element) {
-        // Format: "{event} call {action} -> {target} {swap}"
+        // Format: "{event} call {action} -> {target} {swap} {transition}"
         // or "{event} set {key} = {value}"
         // NOTE: TokenStream adds spaces around punctuation, so "-> #id" becomes "- > # id"
 
@@ -952,12 +955,29 @@ class Azumi {
             let url = `/_azumi/action/${actionName}`;
             let targetSelector = null;
             let swap = "morph";
+            let transition = false;
 
             const arrowIndex = tokens.indexOf("->");
             if (arrowIndex !== -1) {
-                targetSelector = tokens[arrowIndex + 1];
-                if (tokens[arrowIndex + 2]) {
-                    swap = tokens[arrowIndex + 2];
+                // Glue the selector back like az_on::parse_target does:
+                // "#my - box" is "#my-box", and ". card" is ".card"
+                let i = arrowIndex + 1;
+                targetSelector = "";
+                while (i < tokens.length) {
+                    const word = tokens[i];
+                    const glue =
+                        targetSelector === "" ||
+                        /[#.-]$/.test(targetSelector) ||
+                        word.startsWith("-");
+                    if (!glue) break;
+                    targetSelector += word;
+                    i++;
+                }
+                targetSelector = targetSelector || null;
+                // Modifiers after the target: a swap mode and/or "transition"
+                for (const modifier of tokens.slice(i)) {
+                    if (modifier === "transition") transition = true;
+                    else swap = modifier;
                 }
             }
 
@@ -967,6 +987,7 @@ class Azumi {
                 url,
                 target: targetSelector,
                 swap,
+                transition,
             };
         }
 
@@ -1123,6 +1144,12 @@ class Azumi {
         console.log("⏪ Prediction rolled back");
     }
 
+    // CSRF token rendered by azumi::csrf_meta() (CsrfMode::Token only)
+    csrfToken() {
+        const meta = document.querySelector('meta[name="azumi-csrf-token"]');
+        return meta ? meta.getAttribute("content") : null;
+    }
+
     // Server action with optimistic prediction
     async callAction(action, element) {
         // Find scope element
@@ -1154,21 +1181,37 @@ class Azumi {
         }
 
         try {
+            const headers = { "Content-Type": "application/json" };
+            const csrfToken = this.csrfToken();
+            if (csrfToken) headers["X-Azumi-CSRF"] = csrfToken;
+
             const res = await fetch(action.url, {
                 method: "POST",
-                headers: {
-                    "Content-Type": "application/json",
-                },
+                headers,
                 body,
             });
 
             if (!res.ok) throw new Error(`Action failed: ${res.status}`);
 
-            const html = await res.text();
+            // Server-driven directives (azumi::action::ActionResponse)
+            const directives = this.readDirectives(res);
+            if (directives.redirect) {
+                window.location.href = directives.redirect;
+                return;
+            }
+            if (directives.refresh) {
+                window.location.reload();
+                return;
+            }
+
+            // Out-of-band fragments (az-oob) update their own elements by id
+            const { html, fragments } = this.extractOob(await res.text());
+            this.swapOob(fragments);
 
             // OPTIMIZATION: Check if server state matches prediction
             // If prediction was correct, skip morphing to prevent flicker
-            if (predictionResult && scopeElement) {
+            // (unless the server asked to swap somewhere else)
+            if (predictionResult && scopeElement && !directives.retarget) {
                 // Extract az-scope from the response HTML
                 const scopeMatch = html.match(/az-scope='([^']+)'/);
                 if (scopeMatch) {
@@ -1207,22 +1250,22 @@ class Azumi {
 
             // FIXED: Default target to scopeElement (component root), then element
             let target = scopeElement || element;
-            if (action.target) {
-                target = document.querySelector(action.target);
+            const targetSelector = directives.retarget || action.target;
+            if (targetSelector) {
+                target = document.querySelector(targetSelector);
             }
 
-            if (target && window.Idiomorph) {
-                // Morph will reconcile prediction with server truth
-                // Use innerHTML mode to minimize visual artifacts
-                window.Idiomorph.morph(target, html, {
-                    morphStyle: "innerHTML",
-                });
-            } else if (target) {
-                console.warn(
-                    "Idiomorph not loaded, falling back to outerHTML replacement"
-                );
-                target.outerHTML = html;
+            this.swap(
+                target,
+                html,
+                directives.swap || action.swap,
+                directives.transition || action.transition
+            );
+
+            if (directives.pushUrl) {
+                history.pushState({}, "", directives.pushUrl);
             }
+            this.dispatchTriggers(target || element, directives.trigger);
         } catch (err) {
             console.error("Azumi action error:", err);
             // Rollback prediction on error
@@ -1235,6 +1278,138 @@ class Azumi {
         }
     }
 
+    /**
+     * Read Az-* response headers set by ActionResponse
+     */
+    readDirectives(res) {
+        const h = (name) => res.headers.get(name);
+        let trigger = null;
+        if (h("Az-Trigger")) {
+            try {
+                trigger = JSON.parse(h("Az-Trigger"));
+            } catch (e) {
+                console.warn("Invalid Az-Trigger header:", e);
+            }
+        }
+        return {
+            redirect: h("Az-Redirect"),
+            refresh: h("Az-Refresh") === "true",
+            retarget: h("Az-Retarget"),
+            swap: h("Az-Swap"),
+            transition: h("Az-Transition") === "true",
+            pushUrl: h("Az-Push-Url"),
+            trigger,
+        };
+    }
+
+    /**
+     * Put server HTML into the target element
+     * mode: morph | innerHTML | outerHTML | beforebegin | afterbegin |
+     *       beforeend | afterend | delete | none
+     */
+    swap(target, html, mode = "morph", transition = false) {
+        if (!target || mode === "none") return;
+
+        const apply = () => this.applySwap(target, html, mode);
+        if (transition && document.startViewTransition) {
+            document.startViewTransition(apply);
+        } else {
+            apply();
+        }
+    }
+
+    applySwap(target, html, mode) {
+        switch (mode) {
+            case "innerHTML":
+                target.innerHTML = html;
+                break;
+            case "outerHTML":
+                target.outerHTML = html;
+                break;
+            case "beforebegin":
+            case "afterbegin":
+            case "beforeend":
+            case "afterend":
+                target.insertAdjacentHTML(mode, html);
+                break;
+            case "delete":
+                target.remove();
+                break;
+            default:
+                if (mode && mode !== "morph") {
+                    console.warn("Unknown swap mode, using morph:", mode);
+                }
+                if (window.Idiomorph) {
+                    // Morph will reconcile prediction with server truth
+                    // Use innerHTML mode to minimize visual artifacts
+                    window.Idiomorph.morph(target, html, {
+                        morphStyle: "innerHTML",
+                    });
+                } else {
+                    console.warn(
+                        "Idiomorph not loaded, falling back to outerHTML replacement"
+                    );
+                    target.outerHTML = html;
+                }
+        }
+    }
+
+    /**
+     * Split top-level [az-oob] elements out of a response
+     */
+    extractOob(html) {
+        if (!html.includes("az-oob")) return { html, fragments: [] };
+
+        const template = document.createElement("template");
+        template.innerHTML = html;
+        const fragments = Array.from(template.content.children).filter((el) =>
+            el.hasAttribute("az-oob")
+        );
+        fragments.forEach((el) => el.remove());
+
+        return { html: template.innerHTML, fragments };
+    }
+
+    /**
+     * Swap each out-of-band fragment into the element with the same id.
+     * az-oob="beforeend" etc. inserts the fragment's children instead.
+     */
+    swapOob(fragments) {
+        for (const fragment of fragments) {
+            const existing = fragment.id && document.getElementById(fragment.id);
+            if (!existing) {
+                console.warn("az-oob: no element with id", fragment.id);
+                continue;
+            }
+            const mode = fragment.getAttribute("az-oob") || "morph";
+            fragment.removeAttribute("az-oob");
+
+            if (mode !== "morph" && mode !== "outerHTML") {
+                this.swap(existing, fragment.innerHTML, mode);
+            } else if (window.Idiomorph && mode === "morph") {
+                window.Idiomorph.morph(existing, fragment.outerHTML, {
+                    morphStyle: "outerHTML",
+                });
+            } else {
+                existing.replaceWith(fragment);
+            }
+        }
+    }
+
+    /**
+     * Dispatch events requested via Az-Trigger: { "event-name": detail }
+     */
+    dispatchTriggers(element, triggers) {
+        if (!triggers) return;
+        // The target may have been replaced; fall back to document
+        const source = element && element.isConnected ? element : document;
+        for (const [name, detail] of Object.entries(triggers)) {
+            source.dispatchEvent(
+                new CustomEvent(name, { bubbles: true, detail })
+            );
+        }
+    }
+
     // Local state change (no server roundtrip)
     setState(action, element) {
         const scopeElement = element.closest("[az-scope]");
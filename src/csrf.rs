@@ -0,0 +1,170 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderMap, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::Response,
+    Router,
+};
+
+/// Cookie holding the CSRF token (double-submit pattern)
+pub const CSRF_COOKIE: &str = "azumi_csrf";
+
+/// Header the client runtime sends the token back in
+pub const CSRF_HEADER: &str = "x-azumi-csrf";
+
+/// How action and live handlers defend against cross-site requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsrfMode {
+    /// Require the `x-azumi-csrf` header to match the `azumi_csrf` cookie.
+    /// Pages must render `azumi::csrf_meta()` so the client can read the token.
+    Token,
+    /// Reject requests whose `Sec-Fetch-Site` / `Origin` headers point at another site.
+    /// This is the default when `protect` was never applied.
+    Origin,
+    /// No checking at all (tests, trusted internal tools)
+    Disabled,
+}
+
+tokio::task_local! {
+    static CURRENT_TOKEN: String;
+}
+
+/// Apply CSRF protection to a router (usually the one returned by `register_actions`)
+/// Usage: azumi::csrf::protect(app, CsrfMode::Token)
+pub fn protect(router: Router, mode: CsrfMode) -> Router {
+    router.layer(middleware::from_fn_with_state(mode, csrf_middleware))
+}
+
+async fn csrf_middleware(State(mode): State<CsrfMode>, mut req: Request, next: Next) -> Response {
+    req.extensions_mut().insert(mode);
+
+    if mode != CsrfMode::Token {
+        return next.run(req).await;
+    }
+
+    let existing = cookie_value(req.headers(), CSRF_COOKIE).filter(|t| is_valid_token(t));
+    let token = existing.clone().unwrap_or_else(generate_token);
+
+    let mut res = CURRENT_TOKEN.scope(token.clone(), next.run(req)).await;
+
+    // Issue the cookie on first visit
+    if existing.is_none() {
+        let cookie = format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Strict",
+            CSRF_COOKIE, token
+        );
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            res.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+
+    res
+}
+
+/// Render the `<meta>` tag the client runtime reads the token from.
+/// Returns an empty string outside of a `CsrfMode::Token` request.
+/// Usage: html! { <head> { azumi::csrf_meta() } { azumi::azumi_script() } </head> }
+pub fn csrf_meta() -> String {
+    CURRENT_TOKEN
+        .try_with(|token| format!(r#"<meta name="azumi-csrf-token" content="{}">"#, token))
+        .unwrap_or_default()
+}
+
+/// Extractor added to every generated action/live handler.
+/// Rejects the request with 403 when the CSRF check fails.
+pub struct CsrfGuard;
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CsrfGuard {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let mode = parts
+            .extensions
+            .get::<CsrfMode>()
+            .copied()
+            .unwrap_or(CsrfMode::Origin);
+
+        verify(mode, &parts.headers)
+            .map(|_| CsrfGuard)
+            .map_err(|msg| (StatusCode::FORBIDDEN, msg))
+    }
+}
+
+/// Check a request's headers against the given mode
+pub fn verify(mode: CsrfMode, headers: &HeaderMap) -> Result<(), &'static str> {
+    match mode {
+        CsrfMode::Disabled => Ok(()),
+        CsrfMode::Origin => verify_origin(headers),
+        CsrfMode::Token => {
+            let sent = headers
+                .get(CSRF_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .ok_or("Missing CSRF token header")?;
+            let expected = cookie_value(headers, CSRF_COOKIE).ok_or("Missing CSRF cookie")?;
+
+            if !expected.is_empty() && constant_time_eq(sent.as_bytes(), expected.as_bytes()) {
+                Ok(())
+            } else {
+                Err("CSRF token mismatch")
+            }
+        }
+    }
+}
+
+fn verify_origin(headers: &HeaderMap) -> Result<(), &'static str> {
+    // Modern browsers: trust Sec-Fetch-Site when present
+    if let Some(site) = headers.get("sec-fetch-site").and_then(|v| v.to_str().ok()) {
+        return match site {
+            "same-origin" | "none" => Ok(()),
+            _ => Err("Cross-site request blocked"),
+        };
+    }
+
+    // Fallback: compare Origin host against Host
+    if let Some(origin) = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok()) {
+        let host = headers
+            .get(header::HOST)
+            .and_then(|v| v.to_str().ok())
+            .ok_or("Missing Host header")?;
+        let origin_host = origin.split("://").nth(1).unwrap_or(origin);
+        return if origin_host.eq_ignore_ascii_case(host) {
+            Ok(())
+        } else {
+            Err("Cross-origin request blocked")
+        };
+    }
+
+    // Neither header: not a browser-initiated cross-site request
+    Ok(())
+}
+
+fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+fn generate_token() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn is_valid_token(token: &str) -> bool {
+    token.len() == 64 && token.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,96 @@
+//! Partial rendering of named fragments
+//!
+//! ```ignore
+//! #[azumi::component]
+//! fn todo_list(todos: &[Todo]) -> impl Component {
+//!     html! {
+//!         <ul>
+//!             @for todo in todos {
+//!                 <fragment name="row"><li>{&todo.title}</li></fragment>
+//!             }
+//!         </ul>
+//!     }
+//! }
+//!
+//! // In an action: re-render only the rows, with the same props
+//! let rows = azumi::render_fragment(&todo_list(&todos), "row");
+//! ```
+
+use crate::Component;
+use std::cell::RefCell;
+
+// Private-use characters, so the markers never collide with real output
+#[doc(hidden)]
+pub const START: &str = "\u{E000}azumi-fragment\u{E000}";
+#[doc(hidden)]
+pub const END: &str = "\u{E001}azumi-fragment\u{E001}";
+
+thread_local! {
+    static TARGET: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Called by generated code: is `name` the fragment being rendered?
+#[doc(hidden)]
+pub fn is_target(name: &str) -> bool {
+    TARGET.with(|target| target.borrow().as_deref() == Some(name))
+}
+
+/// Render only the `<fragment name="...">` parts of a component.
+/// Every occurrence is included in order (e.g. one per loop iteration).
+/// Returns None when the component rendered no fragment with that name.
+pub fn render_fragment<C: Component + ?Sized>(component: &C, name: &str) -> Option<String> {
+    let html = {
+        let _target = TargetGuard::set(name);
+        crate::render_to_string(component)
+    };
+    extract(&html)
+}
+
+/// Sets the fragment being rendered, restoring the previous one on drop
+/// (so a panicking component doesn't leave the thread rendering markers)
+struct TargetGuard(Option<String>);
+
+impl TargetGuard {
+    fn set(name: &str) -> Self {
+        TargetGuard(TARGET.with(|target| target.replace(Some(name.to_string()))))
+    }
+}
+
+impl Drop for TargetGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        TARGET.with(|target| *target.borrow_mut() = previous);
+    }
+}
+
+fn extract(html: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut found = false;
+    let mut depth = 0usize;
+    let mut rest = html;
+
+    loop {
+        let next_start = rest.find(START);
+        let next_end = rest.find(END);
+        let (idx, is_start) = match (next_start, next_end) {
+            (Some(s), Some(e)) if s < e => (s, true),
+            (Some(s), None) => (s, true),
+            (_, Some(e)) => (e, false),
+            (None, None) => break,
+        };
+
+        if depth > 0 {
+            out.push_str(&rest[..idx]);
+        }
+        if is_start {
+            depth += 1;
+            found = true;
+            rest = &rest[idx + START.len()..];
+        } else {
+            depth = depth.saturating_sub(1);
+            rest = &rest[idx + END.len()..];
+        }
+    }
+
+    found.then_some(out)
+}
@@ -0,0 +1,268 @@
+//! Internationalization: locale negotiation and translated messages
+//!
+//! ```ignore
+//! // locales/en.json: {"greeting": "Hello, {name}!"}
+//! // locales/fr.ftl:  greeting = Bonjour, { $name } !
+//!
+//! let app = azumi::i18n::localize(app, &["en", "fr"]);
+//!
+//! html! { <html><body><p>{azumi::t!("greeting", name = user)}</p></body></html> }
+//! // <html lang="fr" dir="ltr">... for `Accept-Language: fr-CA,fr;q=0.9`
+//! ```
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, StatusCode},
+    middleware::{self, Next},
+    response::Response,
+    Router,
+};
+use std::cell::RefCell;
+use std::fmt;
+
+// Languages written right-to-left
+const RTL_LANGUAGES: &[&str] = &["ar", "dv", "fa", "he", "ckb", "ps", "sd", "ug", "ur", "yi"];
+
+/// A BCP 47 language tag such as `en`, `fr-CA` or `ar`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale(String);
+
+impl Locale {
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self(tag.into())
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.0
+    }
+
+    /// Primary language subtag (`fr` for `fr-CA`)
+    pub fn language(&self) -> &str {
+        self.0.split(['-', '_']).next().unwrap_or(&self.0)
+    }
+
+    pub fn is_rtl(&self) -> bool {
+        let language = self.language().to_ascii_lowercase();
+        RTL_LANGUAGES.contains(&language.as_str())
+    }
+
+    /// Value for the `dir` attribute
+    pub fn dir(&self) -> &'static str {
+        if self.is_rtl() {
+            "rtl"
+        } else {
+            "ltr"
+        }
+    }
+
+    fn matches(&self, tag: &str) -> bool {
+        self.0.eq_ignore_ascii_case(tag)
+    }
+}
+
+impl From<&str> for Locale {
+    fn from(tag: &str) -> Self {
+        Self::new(tag)
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_LOCALE: Locale;
+}
+
+thread_local! {
+    static LOCALE_OVERRIDE: RefCell<Option<Locale>> = const { RefCell::new(None) };
+}
+
+/// Negotiate each request's locale from `Accept-Language`
+/// Usage: azumi::i18n::localize(app, &["en", "fr"]) - the first locale is the default
+pub fn localize(router: Router, supported: &'static [&'static str]) -> Router {
+    router.layer(middleware::from_fn_with_state(supported, locale_middleware))
+}
+
+async fn locale_middleware(
+    State(supported): State<&'static [&'static str]>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let accept = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let locale = negotiate(accept, supported);
+    req.extensions_mut().insert(locale.clone());
+
+    let mut res = CURRENT_LOCALE.scope(locale, next.run(req)).await;
+    res.headers_mut()
+        .append(header::VARY, header::ACCEPT_LANGUAGE.into());
+    res
+}
+
+/// Pick the best supported locale for an `Accept-Language` header.
+/// Exact tags win, then a shared language (`fr-CA` -> `fr`); otherwise the first supported locale.
+pub fn negotiate(accept_language: &str, supported: &[&str]) -> Locale {
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|part| {
+            let mut pieces = part.split(';');
+            let tag = pieces.next()?.trim();
+            let quality = pieces
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse().ok())
+                .unwrap_or(1.0);
+            (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    // Stable sort keeps header order for equal weights
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    for (tag, _) in &ranges {
+        let wanted = Locale::new(*tag);
+        if let Some(exact) = supported.iter().find(|s| wanted.matches(s)) {
+            return Locale::new(*exact);
+        }
+        if let Some(partial) = supported.iter().find(|s| {
+            Locale::new(**s)
+                .language()
+                .eq_ignore_ascii_case(wanted.language())
+        }) {
+            return Locale::new(*partial);
+        }
+    }
+
+    Locale::new(supported.first().copied().unwrap_or("en"))
+}
+
+/// Render with a fixed locale outside of a request (tests, emails, background jobs)
+pub fn with_locale<R>(locale: impl Into<Locale>, f: impl FnOnce() -> R) -> R {
+    let _override = OverrideGuard::set(locale.into());
+    f()
+}
+
+/// Sets the locale override, restoring the previous one on drop
+/// (so a panicking render doesn't leave the thread in that locale)
+struct OverrideGuard(Option<Locale>);
+
+impl OverrideGuard {
+    fn set(locale: Locale) -> Self {
+        OverrideGuard(LOCALE_OVERRIDE.with(|l| l.replace(Some(locale))))
+    }
+}
+
+impl Drop for OverrideGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        LOCALE_OVERRIDE.with(|l| *l.borrow_mut() = previous);
+    }
+}
+
+/// The locale being rendered, if any
+pub fn current() -> Option<Locale> {
+    LOCALE_OVERRIDE
+        .with(|l| l.borrow().clone())
+        .or_else(|| CURRENT_LOCALE.try_with(|l| l.clone()).ok())
+}
+
+/// Extractor for the negotiated locale (requires `localize`)
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Locale>().cloned().ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Locale extractor requires azumi::i18n::localize",
+        ))
+    }
+}
+
+/// A message from the catalogs, generated by `t!`
+#[doc(hidden)]
+pub struct Message {
+    pub key: &'static str,
+    pub translations: &'static [(&'static str, &'static str)],
+}
+
+impl Message {
+    /// Translation for the current locale: exact tag, then language, then English, then the first catalog
+    pub fn text(&self) -> &'static str {
+        let locale = current();
+        let find = |pred: &dyn Fn(&str) -> bool| {
+            self.translations
+                .iter()
+                .find(|(tag, _)| pred(tag))
+                .map(|(_, text)| *text)
+        };
+
+        locale
+            .as_ref()
+            .and_then(|l| find(&|tag| l.matches(tag)))
+            .or_else(|| {
+                let language = locale.as_ref()?.language();
+                find(&|tag| Locale::new(tag).language().eq_ignore_ascii_case(language))
+            })
+            .or_else(|| find(&|tag| tag == "en"))
+            .or_else(|| self.translations.first().map(|(_, text)| *text))
+            .unwrap_or(self.key)
+    }
+
+    /// Fill `{name}` placeholders
+    pub fn format(&self, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let text = self.text();
+        if args.is_empty() {
+            return text.to_string();
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let arg = after.find('}').and_then(|end| {
+                args.iter()
+                    .find(|(n, _)| *n == &after[..end])
+                    .map(|a| (end, a.1))
+            });
+            match arg {
+                Some((end, value)) => {
+                    out.push_str(&value.to_string());
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Called by generated code for `<html>`: adds lang/dir from the current locale
+#[doc(hidden)]
+pub fn write_lang_attrs(f: &mut fmt::Formatter<'_>, lang: bool, dir: bool) -> fmt::Result {
+    if let Some(locale) = current() {
+        if lang {
+            write!(f, " lang=\"{}\"", crate::Escaped(locale.tag()))?;
+        }
+        if dir {
+            write!(f, " dir=\"{}\"", locale.dir())?;
+        }
+    }
+    Ok(())
+}
+
+/// Called by generated code for `<html lang="...">`: adds the dir of that language
+#[doc(hidden)]
+pub fn write_dir_for(f: &mut fmt::Formatter<'_>, lang: &str) -> fmt::Result {
+    write!(f, " dir=\"{}\"", Locale::new(lang).dir())
+}
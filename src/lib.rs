@@ -1,11 +1,25 @@
 pub mod prelude {
-    pub use crate::action::Action;
-    pub use crate::{action, azumi_script, component, head, html, live, live_impl, Component};
+    pub use crate::action::{Action, ActionResponse, Swap};
+    pub use crate::attrs::Attributes;
+    pub use crate::{
+        action, azumi_script, component, csrf_meta, head, html, live, live_impl, t, Component,
+    };
 }
 
-pub use azumi_macros::{action, component, head, html, live, live_impl};
+pub use azumi_macros::{
+    action, component, head, html, include_html, live, live_impl, markdown, t, theme,
+};
 pub mod action;
+pub mod attrs;
+pub mod csrf;
+pub mod fragment;
+pub mod i18n;
 pub mod hot_reload;
+pub mod pretty;
+pub mod styles;
+pub use attrs::Attributes;
+pub use csrf::csrf_meta;
+pub use fragment::render_fragment;
 pub use inventory;
 
 pub trait Component {
@@ -77,7 +91,13 @@ pub fn render_to_string<C: Component + ?Sized>(component: &C) -> String {
             self.0.render(f)
         }
     }
-    format!("{}", DisplayWrapper(component))
+    let html = format!("{}", DisplayWrapper(component));
+
+    // Readable view-source during development
+    #[cfg(feature = "pretty")]
+    let html = pretty::pretty_print(&html);
+
+    html
 }
 
 pub struct Escaped<T: std::fmt::Display>(pub T);
@@ -127,66 +147,46 @@ pub fn js<T: std::fmt::Debug>(v: T) -> String {
     format!("{:?}", v)
 }
 
-/// Generate a unique scope ID for CSS scoping
-pub fn generate_scope_id() -> String {
-    use std::sync::atomic::{AtomicU64, Ordering};
-    static COUNTER: AtomicU64 = AtomicU64::new(0);
-    let id = COUNTER.fetch_add(1, Ordering::Relaxed);
-    format!("s{:x}", id)
-}
-
-/// Transform CSS selectors to include scope attribute
-/// All CSS is automatically scoped - no escape hatches!
-pub fn scope_css(css: &str, scope_id: &str) -> String {
-    let scope_attr = format!("[data-{}]", scope_id);
-    let mut result = String::new();
-    let mut in_rule = false;
-    let mut selector_buffer = String::new();
-
-    for ch in css.chars() {
-        match ch {
-            '{' if !in_rule => {
-                // Found opening brace - scope the selector buffer
-                let selectors: Vec<&str> = selector_buffer.split(',').collect();
-                let scoped: Vec<String> = selectors
-                    .iter()
-                    .filter(|s| !s.trim().is_empty())
-                    .map(|s| {
-                        let trimmed = s.trim();
-                        // Skip @-rules and comments
-                        if trimmed.starts_with('@') || trimmed.starts_with("/*") {
-                            trimmed.to_string()
-                        } else {
-                            format!("{}{}", trimmed, scope_attr)
-                        }
-                    })
-                    .collect();
-
-                result.push_str(&scoped.join(", "));
-                result.push('{');
-                selector_buffer.clear();
-                in_rule = true;
-            }
-            '}' if in_rule => {
-                result.push('}');
-                in_rule = false;
-            }
-            _ => {
-                if in_rule {
-                    result.push(ch);
-                } else {
-                    selector_buffer.push(ch);
-                }
-            }
+/// Position of the current item in `@for (item, loop) in items { ... }`
+///
+/// Useful for separators, zebra striping and `aria-posinset`/`aria-setsize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopContext {
+    /// Zero-based index
+    pub index: usize,
+    /// One-based position (for `aria-posinset`)
+    pub position: usize,
+    /// Total number of items
+    pub len: usize,
+    pub first: bool,
+    pub last: bool,
+}
+
+impl LoopContext {
+    pub fn new(index: usize, len: usize) -> Self {
+        Self {
+            index,
+            position: index + 1,
+            len,
+            first: index == 0,
+            last: index + 1 == len,
         }
     }
 
-    // Handle any remaining selector buffer (malformed CSS)
-    if !selector_buffer.trim().is_empty() {
-        result.push_str(&selector_buffer);
+    pub fn is_even(&self) -> bool {
+        self.index.is_multiple_of(2)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        !self.is_even()
     }
+}
 
-    result
+/// Scope CSS the way `<style>` blocks in components are scoped: every class
+/// name gets a `-{suffix}`, so `.button` becomes `.button-{suffix}`.
+/// Rules inside @media, @supports, @container and @layer are renamed too.
+pub fn scope_css(css: &str, suffix: &str) -> String {
+    azumi_css::rename_classes(css, suffix)
 }
 
 // ============================================================================
@@ -0,0 +1,180 @@
+//! Debug pretty-printer for rendered HTML
+//!
+//! With the `pretty` feature, `render_to_string` runs its output through
+//! `pretty_print` so view-source is readable during development. Only block
+//! elements get their own line; inline content, `<pre>`, `<textarea>`,
+//! `<script>` and `<style>` are copied verbatim.
+
+const INDENT: &str = "  ";
+
+// Elements that start a new indented line and nest their children
+const BLOCK: &[&str] = &[
+    "html",
+    "head",
+    "body",
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "main",
+    "nav",
+    "aside",
+    "ul",
+    "ol",
+    "li",
+    "dl",
+    "dt",
+    "dd",
+    "table",
+    "thead",
+    "tbody",
+    "tfoot",
+    "tr",
+    "td",
+    "th",
+    "caption",
+    "form",
+    "fieldset",
+    "legend",
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+    "figure",
+    "figcaption",
+    "details",
+    "summary",
+    "dialog",
+    "title",
+];
+
+// Elements without children that still get their own line
+const LINE: &[&str] = &["meta", "link", "hr", "base"];
+
+// Elements whose content is copied as-is
+const RAW: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Indent block-level elements of an HTML string
+pub fn pretty_print(html: &str) -> String {
+    let mut out = String::with_capacity(html.len() * 2);
+    // Open block elements, and whether each has had a block child yet
+    let mut stack: Vec<(String, bool)> = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map(|i| i + 3).unwrap_or(rest.len());
+            new_line(&mut out, stack.len());
+            mark_block_child(&mut stack);
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = tag_end(rest);
+        let tag = &rest[..end];
+        rest = &rest[end..];
+        let (name, is_close) = tag_name(tag);
+
+        if !is_close && RAW.contains(&name.as_str()) {
+            // Copy through the matching close tag untouched
+            let close = format!("</{}", name);
+            let body_end = rest
+                .find(&close)
+                .map(|i| i + tag_end(&rest[i..]))
+                .unwrap_or(rest.len());
+            if name != "textarea" {
+                new_line(&mut out, stack.len());
+                mark_block_child(&mut stack);
+            }
+            out.push_str(tag);
+            out.push_str(&rest[..body_end]);
+            rest = &rest[body_end..];
+        } else if BLOCK.contains(&name.as_str()) {
+            if is_close {
+                // Pop to the matching element (tolerates unbalanced markup)
+                while let Some((open, had_block)) = stack.pop() {
+                    if open == name {
+                        if had_block {
+                            new_line(&mut out, stack.len());
+                        }
+                        break;
+                    }
+                }
+                out.push_str(tag);
+            } else {
+                new_line(&mut out, stack.len());
+                mark_block_child(&mut stack);
+                out.push_str(tag);
+                if !tag.ends_with("/>") {
+                    stack.push((name, false));
+                }
+            }
+        } else if LINE.contains(&name.as_str()) || tag.starts_with("<!") {
+            if !is_close {
+                new_line(&mut out, stack.len());
+                mark_block_child(&mut stack);
+            }
+            out.push_str(tag);
+        } else {
+            out.push_str(tag);
+        }
+    }
+
+    out
+}
+
+fn new_line(out: &mut String, depth: usize) {
+    if !out.is_empty() {
+        out.push('\n');
+        for _ in 0..depth {
+            out.push_str(INDENT);
+        }
+    }
+}
+
+fn mark_block_child(stack: &mut [(String, bool)]) {
+    if let Some(parent) = stack.last_mut() {
+        parent.1 = true;
+    }
+}
+
+// Index just past the closing '>' of the tag at the start of `s`, skipping quoted values
+fn tag_end(s: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+fn tag_name(tag: &str) -> (String, bool) {
+    let inner = tag.trim_start_matches('<');
+    let (is_close, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let name = inner
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    (name, is_close)
+}
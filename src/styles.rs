@@ -0,0 +1,301 @@
+//! App-wide stylesheet bundle
+//!
+//! Every `<style>` block in `html!` registers its CSS here at compile time,
+//! keyed by a hash of its content. Serving the bundle stops components from
+//! inlining their CSS, so a list of 100 cards no longer repeats it 100 times.
+//!
+//! ```ignore
+//! let app = azumi::styles::serve_bundle(app);
+//!
+//! html! { <head>{azumi::styles::stylesheet_link()}</head> }
+//! ```
+//!
+//! Alternatively, `render_with_critical_css` inlines only the CSS of the
+//! components a page actually rendered, once, in its `<head>`.
+
+use axum::{
+    extract::Query,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Path the bundle is served from
+pub const BUNDLE_PATH: &str = "/azumi.css";
+
+/// One stylesheet from an html! macro (generated code submits these)
+pub struct Stylesheet {
+    pub id: &'static str,
+    pub css: &'static str,
+    pub global: bool,
+}
+
+inventory::collect!(Stylesheet);
+
+/// A class another component's `<style>` shares with `@export { ... }`, as
+/// `card_component::exports::CHIP`. Its rules are renamed but not scoped to the
+/// exporting component, so `composes: chip from card_component;` can use them.
+pub struct SharedClass {
+    pub class: &'static str,
+    pub sheet: &'static Stylesheet,
+}
+
+impl SharedClass {
+    pub const fn as_str(&self) -> &'static str {
+        self.class
+    }
+}
+
+impl std::fmt::Display for SharedClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.class)
+    }
+}
+
+// Composed class bindings are joined at compile time so they stay `&'static str`:
+// const BYTES: [u8; joined_len(PARTS)] = join_classes(PARTS);
+
+#[doc(hidden)]
+pub const fn joined_len(parts: &[&str]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        if i > 0 {
+            len += 1;
+        }
+        len += parts[i].len();
+        i += 1;
+    }
+    len
+}
+
+#[doc(hidden)]
+pub const fn join_classes<const N: usize>(parts: &[&str]) -> [u8; N] {
+    let mut out = [0u8; N];
+    let mut pos = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        if i > 0 {
+            out[pos] = b' ';
+            pos += 1;
+        }
+        let bytes = parts[i].as_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            out[pos] = bytes[j];
+            pos += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    out
+}
+
+#[doc(hidden)]
+pub const fn class_str(bytes: &'static [u8]) -> &'static str {
+    match std::str::from_utf8(bytes) {
+        Ok(classes) => classes,
+        Err(_) => panic!("class names are UTF-8"),
+    }
+}
+
+/// Called by generated code: write the stylesheets of composed classes, each once
+#[doc(hidden)]
+pub fn write_shared(f: &mut std::fmt::Formatter<'_>, classes: &[&SharedClass]) -> std::fmt::Result {
+    let mut written: Vec<&str> = Vec::new();
+    for class in classes {
+        let sheet = class.sheet;
+        if written.contains(&sheet.id) {
+            continue;
+        }
+        written.push(sheet.id);
+        if should_inline(sheet.id, sheet.css) {
+            write!(f, "<style>{}</style>", sheet.css)?;
+        }
+    }
+    Ok(())
+}
+
+/// The combined stylesheet and its content hash
+pub struct Bundle {
+    pub css: String,
+    pub hash: String,
+}
+
+static BUNDLED: AtomicBool = AtomicBool::new(false);
+
+// Marks where collected critical CSS goes (private-use characters never collide with output)
+const CRITICAL_CSS_MARKER: &str = "\u{E002}azumi-critical-css\u{E002}";
+
+thread_local! {
+    // Stylesheets used by the current render_with_critical_css, in first-use order
+    static COLLECTED: RefCell<Option<Vec<(&'static str, &'static str)>>> = const { RefCell::new(None) };
+}
+
+/// Has `serve_bundle` been applied?
+pub fn is_bundled() -> bool {
+    BUNDLED.load(Ordering::Relaxed)
+}
+
+/// Called by generated code: should this stylesheet be written inline right here?
+/// No when the bundle is served; when collecting, it is recorded once instead.
+#[doc(hidden)]
+pub fn should_inline(id: &'static str, css: &'static str) -> bool {
+    if is_bundled() {
+        return false;
+    }
+    COLLECTED.with(|collected| match collected.borrow_mut().as_mut() {
+        Some(sheets) => {
+            if !sheets.iter().any(|(seen, _)| *seen == id) {
+                sheets.push((id, css));
+            }
+            false
+        }
+        None => true,
+    })
+}
+
+/// Render a page with the CSS of every component it used inlined once.
+/// The styles replace `{azumi::styles::critical_css()}` if the page has it,
+/// otherwise they go right before `</head>` (or at the start without a head).
+pub fn render_with_critical_css<C: crate::Component + ?Sized>(component: &C) -> String {
+    let collecting = CollectGuard::start();
+    let html = crate::render_to_string(component);
+    let sheets = collecting.finish();
+
+    let styles = if sheets.is_empty() {
+        String::new()
+    } else {
+        let css: String = sheets.iter().map(|(_, css)| *css).collect();
+        format!("<style data-azumi-critical>{}</style>", css)
+    };
+
+    if html.contains(CRITICAL_CSS_MARKER) {
+        html.replacen(CRITICAL_CSS_MARKER, &styles, 1)
+            .replace(CRITICAL_CSS_MARKER, "")
+    } else if let Some(head_end) = html.find("</head>") {
+        format!("{}{}{}", &html[..head_end], styles, &html[head_end..])
+    } else {
+        format!("{}{}", styles, html)
+    }
+}
+
+/// Starts collecting stylesheets, restoring the previous collector on drop
+/// (so a panicking component doesn't leave the thread collecting)
+struct CollectGuard(Option<Vec<(&'static str, &'static str)>>);
+
+impl CollectGuard {
+    fn start() -> Self {
+        CollectGuard(COLLECTED.with(|c| c.replace(Some(Vec::new()))))
+    }
+
+    /// The stylesheets collected since `start`
+    fn finish(self) -> Vec<(&'static str, &'static str)> {
+        COLLECTED
+            .with(|c| c.borrow_mut().take())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for CollectGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        COLLECTED.with(|c| *c.borrow_mut() = previous);
+    }
+}
+
+/// Where `render_with_critical_css` puts the collected CSS
+/// Usage: html! { <head>{azumi::styles::critical_css()}</head> }
+pub fn critical_css() -> impl crate::Component {
+    crate::from_fn(|f| {
+        let collecting = COLLECTED.with(|c| c.borrow().is_some());
+        if collecting {
+            f.write_str(CRITICAL_CSS_MARKER)?;
+        }
+        Ok(())
+    })
+}
+
+/// All registered stylesheets, deduplicated by id.
+/// Order is deterministic: global sheets first, then scoped, each sorted by id.
+pub fn bundle() -> &'static Bundle {
+    static BUNDLE: OnceLock<Bundle> = OnceLock::new();
+    BUNDLE.get_or_init(|| {
+        let sheets: BTreeMap<(bool, &str), &str> = inventory::iter::<Stylesheet>
+            .into_iter()
+            .map(|sheet| ((!sheet.global, sheet.id), sheet.css))
+            .collect();
+
+        let mut css = String::new();
+        for sheet in sheets.values() {
+            css.push_str(sheet);
+            css.push('\n');
+        }
+
+        let mut hasher = DefaultHasher::new();
+        css.hash(&mut hasher);
+        let hash = format!("{:016x}", hasher.finish());
+        Bundle { css, hash }
+    })
+}
+
+/// Serve the bundle at `/azumi.css` and stop inlining component CSS
+/// Usage: azumi::styles::serve_bundle(app)
+pub fn serve_bundle(router: Router) -> Router {
+    BUNDLED.store(true, Ordering::Relaxed);
+    router.route(BUNDLE_PATH, get(bundle_handler))
+}
+
+#[derive(serde::Deserialize)]
+struct BundleQuery {
+    v: Option<String>,
+}
+
+async fn bundle_handler(Query(query): Query<BundleQuery>, headers: HeaderMap) -> Response {
+    let bundle = bundle();
+    let etag = format!("\"{}\"", bundle.hash);
+
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|v| v.as_bytes() == etag.as_bytes())
+    {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    // The versioned URL from stylesheet_link() never changes content
+    let cache_control = if query.v.as_deref() == Some(bundle.hash.as_str()) {
+        "public, max-age=31536000, immutable"
+    } else {
+        "no-cache"
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/css; charset=utf-8".to_string()),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+            (header::ETAG, etag),
+        ],
+        bundle.css.clone(),
+    )
+        .into_response()
+}
+
+/// `<link>` to the content-hashed bundle
+/// Usage: html! { <head>{azumi::styles::stylesheet_link()}</head> }
+pub fn stylesheet_link() -> impl crate::Component {
+    crate::from_fn(|f| {
+        write!(
+            f,
+            r#"<link rel="stylesheet" href="{}?v={}">"#,
+            BUNDLE_PATH,
+            bundle().hash
+        )
+    })
+}
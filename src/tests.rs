@@ -1,12 +1,12 @@
 #[cfg(test)]
 mod tests {
-    use crate::{generate_scope_id, scope_css};
+    use crate::scope_css;
 
     #[test]
     fn test_scope_css_basic() {
         let css = ".button { color: red; }";
         let scoped = scope_css(css, "abc");
-        assert!(scoped.contains(".button[data-abc]"));
+        assert!(scoped.contains(".button-abc"));
         assert!(scoped.contains("color: red;"));
     }
 
@@ -14,14 +14,86 @@ mod tests {
     fn test_scope_css_multiple_selectors() {
         let css = ".button, .link { color: blue; }";
         let scoped = scope_css(css, "s123");
-        assert!(scoped.contains(".button[data-s123], .link[data-s123]"));
+        assert!(scoped.contains(".button-s123, .link-s123"));
     }
 
     #[test]
-    fn test_generate_scope_id() {
-        let id1 = generate_scope_id();
-        let id2 = generate_scope_id();
-        assert_ne!(id1, id2);
-        assert!(id1.starts_with('s'));
+    fn test_scope_css_nested_at_rules() {
+        let css = "@media (max-width: 600px) { .button { color: red; } }";
+        let scoped = scope_css(css, "abc");
+        assert!(scoped.contains("@media"));
+        assert!(scoped.contains(".button-abc"));
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> axum::http::HeaderMap {
+        let mut map = axum::http::HeaderMap::new();
+        for (k, v) in pairs {
+            map.insert(*k, axum::http::HeaderValue::from_static(v));
+        }
+        map
+    }
+
+    #[test]
+    fn test_csrf_origin_mode() {
+        use crate::csrf::{verify, CsrfMode};
+
+        let same = headers(&[("sec-fetch-site", "same-origin")]);
+        assert!(verify(CsrfMode::Origin, &same).is_ok());
+
+        let cross = headers(&[("sec-fetch-site", "cross-site")]);
+        assert!(verify(CsrfMode::Origin, &cross).is_err());
+
+        let origin_ok = headers(&[("origin", "https://example.com"), ("host", "example.com")]);
+        assert!(verify(CsrfMode::Origin, &origin_ok).is_ok());
+
+        let origin_bad = headers(&[("origin", "https://evil.com"), ("host", "example.com")]);
+        assert!(verify(CsrfMode::Origin, &origin_bad).is_err());
+
+        // Non-browser clients send neither header
+        assert!(verify(CsrfMode::Origin, &headers(&[])).is_ok());
+    }
+
+    #[test]
+    fn test_csrf_token_mode() {
+        use crate::csrf::{verify, CsrfMode};
+
+        let ok = headers(&[
+            ("x-azumi-csrf", "abc123"),
+            ("cookie", "theme=dark; azumi_csrf=abc123"),
+        ]);
+        assert!(verify(CsrfMode::Token, &ok).is_ok());
+
+        let mismatch = headers(&[("x-azumi-csrf", "abc124"), ("cookie", "azumi_csrf=abc123")]);
+        assert!(verify(CsrfMode::Token, &mismatch).is_err());
+
+        let missing = headers(&[("cookie", "azumi_csrf=abc123")]);
+        assert!(verify(CsrfMode::Token, &missing).is_err());
+
+        assert!(verify(CsrfMode::Disabled, &headers(&[])).is_ok());
+    }
+
+    #[test]
+    fn test_csrf_meta_outside_request() {
+        assert_eq!(crate::csrf_meta(), "");
+    }
+
+    #[test]
+    fn test_pretty_print_blocks() {
+        use crate::pretty::pretty_print;
+        let html = r#"<!DOCTYPE html><html><head><meta charset="utf-8"><title>Hi</title></head><body><div class="a"><p>Hello <b>world</b></p><ul><li>one</li></ul></div></body></html>"#;
+        assert_eq!(
+            pretty_print(html),
+            "<!DOCTYPE html>\n<html>\n  <head>\n    <meta charset=\"utf-8\">\n    <title>Hi</title>\n  </head>\n  <body>\n    <div class=\"a\">\n      <p>Hello <b>world</b></p>\n      <ul>\n        <li>one</li>\n      </ul>\n    </div>\n  </body>\n</html>"
+        );
+    }
+
+    #[test]
+    fn test_pretty_print_keeps_raw_content() {
+        use crate::pretty::pretty_print;
+        let html = "<div><pre>  a\n<div>b</div></pre><textarea> x </textarea><span az-scope='{\"a\":\">\"}'>s</span></div>";
+        assert_eq!(
+            pretty_print(html),
+            "<div>\n  <pre>  a\n<div>b</div></pre><textarea> x </textarea><span az-scope='{\"a\":\">\"}'>s</span>\n</div>"
+        );
     }
 }
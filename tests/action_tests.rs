@@ -0,0 +1,222 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use azumi::html;
+use tower::ServiceExt;
+
+async fn require_auth(req: Request, next: Next) -> Response {
+    if req.headers().contains_key("x-test-auth") {
+        next.run(req).await
+    } else {
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
+
+#[azumi::action(guard = require_auth)]
+pub async fn guarded_greet(name: String) -> impl azumi::Component {
+    html! { <p>{name}</p> }
+}
+
+#[azumi::action]
+pub async fn save_note(text: String) -> azumi::action::ActionResponse {
+    azumi::action::ActionResponse::new(&html! { <p>{text}</p> })
+        .retarget("#notes")
+        .push_url("/notes")
+        .toast("Saved")
+}
+
+#[azumi::action]
+pub async fn add_to_cart(count: u32) -> azumi::action::ActionResponse {
+    let badge_id = "cart_badge";
+    azumi::action::ActionResponse::new(&html! { <p>"Added"</p> }).oob(&html! {
+        <span id={badge_id} az-oob>{count + 1}</span>
+    })
+}
+
+#[azumi::action]
+pub async fn append_item(text: String) -> azumi::action::ActionResponse {
+    azumi::action::ActionResponse::new(&html! { <li>{text}</li> })
+        .swap(azumi::action::Swap::BeforeEnd)
+        .transition()
+}
+
+#[azumi::live]
+pub struct Switch {
+    pub on: bool,
+}
+
+#[azumi::live_impl]
+impl Switch {
+    #[guard(require_auth)]
+    pub fn flip(&mut self) {
+        self.on = !self.on;
+    }
+}
+
+fn post(path: &str, body: &'static str, auth: bool) -> Request {
+    let mut builder = Request::post(path).header("content-type", "application/json");
+    if auth {
+        builder = builder.header("x-test-auth", "1");
+    }
+    builder.body(Body::from(body)).unwrap()
+}
+
+async fn body_string(res: Response) -> String {
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_action_guard() {
+    let app = azumi::action::register_actions(axum::Router::new());
+
+    let res = app
+        .clone()
+        .oneshot(post("/_azumi/action/guarded_greet", "\"Ada\"", false))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = app
+        .oneshot(post("/_azumi/action/guarded_greet", "\"Ada\"", true))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(body_string(res).await.contains("<p>Ada</p>"));
+}
+
+#[tokio::test]
+async fn test_live_method_guard() {
+    let app = azumi::action::register_actions(axum::Router::new());
+
+    let res = app
+        .clone()
+        .oneshot(post("/_azumi/action/Switch/flip", r#"{"on":false}"#, false))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = app
+        .oneshot(post("/_azumi/action/Switch/flip", r#"{"on":false}"#, true))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(body_string(res).await, r#"{"on":true}"#);
+}
+
+#[tokio::test]
+async fn test_register_actions_with_hook() {
+    // Block everything through the global hook
+    let app = azumi::action::register_actions_with(axum::Router::new(), |_path, route| {
+        route.layer(axum::middleware::from_fn(
+            |_req: Request, _next: Next| async { StatusCode::FORBIDDEN },
+        ))
+    });
+
+    let res = app
+        .oneshot(post("/_azumi/action/guarded_greet", "\"Ada\"", true))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_action_response_directives() {
+    let app = azumi::action::register_actions(axum::Router::new());
+
+    let res = app
+        .oneshot(post("/_azumi/action/save_note", "\"hi\"", false))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let headers = res.headers();
+    assert_eq!(headers["az-retarget"], "#notes");
+    assert_eq!(headers["az-push-url"], "/notes");
+    assert_eq!(
+        headers["az-trigger"],
+        r#"{"azumi:toast":{"message":"Saved"}}"#
+    );
+    assert!(headers.get("az-redirect").is_none());
+    assert!(body_string(res).await.contains("<p>hi</p>"));
+}
+
+#[tokio::test]
+async fn test_action_response_oob() {
+    let app = azumi::action::register_actions(axum::Router::new());
+
+    let res = app
+        .oneshot(post("/_azumi/action/add_to_cart", "2", false))
+        .await
+        .unwrap();
+    let body = body_string(res).await;
+    assert!(body.starts_with("<p>Added</p>"));
+    assert!(body.contains(r#"<span id="cart_badge" az-oob>3</span>"#));
+}
+
+#[tokio::test]
+async fn test_action_response_swap_mode() {
+    let app = azumi::action::register_actions(axum::Router::new());
+
+    let res = app
+        .oneshot(post("/_azumi/action/append_item", "\"milk\"", false))
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["az-swap"], "beforeend");
+    assert_eq!(res.headers()["az-transition"], "true");
+}
+
+#[test]
+fn test_swap_modes_in_markup() {
+    let count_id = "count";
+    let list = html! {
+        <button az-on={click call append_item -> #list beforeend transition}>"Add"</button>
+        <span id={count_id} az-oob="innerHTML">"2"</span>
+    };
+    let out = azumi::render_to_string(&list);
+    assert!(out.contains("beforeend transition"));
+    assert!(out.contains(r#"az-oob="innerHTML""#));
+}
+
+#[test]
+fn test_action_response_non_ascii_directives() {
+    let res = azumi::action::ActionResponse::new(&html! { <p>"ok"</p> })
+        .toast("Enregistré 👍")
+        .push_url("/notes/café")
+        .redirect("/ß?q=a b")
+        .retarget("#liste-é")
+        .into_response();
+
+    let headers = res.headers();
+    assert_eq!(
+        headers["az-trigger"],
+        r#"{"azumi:toast":{"message":"Enregistr\u00e9 \ud83d\udc4d"}}"#
+    );
+    let detail: serde_json::Value =
+        serde_json::from_slice(headers["az-trigger"].as_bytes()).unwrap();
+    assert_eq!(detail["azumi:toast"]["message"], "Enregistré 👍");
+    assert_eq!(headers["az-push-url"], "/notes/caf%C3%A9");
+    assert_eq!(headers["az-redirect"], "/%C3%9F?q=a%20b");
+    assert_eq!(headers["az-retarget"], "#liste-\\e9 ");
+}
+
+#[test]
+fn test_action_response_control_characters() {
+    let res = azumi::action::ActionResponse::new(&html! { <p>"ok"</p> })
+        .toast("a\u{7f}b\tc")
+        .retarget("#a\u{7f}")
+        .into_response();
+
+    let headers = res.headers();
+    assert_eq!(
+        headers["az-trigger"],
+        r#"{"azumi:toast":{"message":"a\u007fb\tc"}}"#
+    );
+    assert_eq!(headers["az-retarget"], "#a\\7f ");
+}
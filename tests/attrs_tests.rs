@@ -0,0 +1,109 @@
+use azumi::{html, Attributes};
+
+#[test]
+fn test_optional_attributes() {
+    let render = |title: Option<&str>, busy: Option<bool>| {
+        azumi::render_to_string(&html! {
+            <button title={title} disabled={busy}>"Save"</button>
+        })
+    };
+    assert_eq!(render(None, None), "<button>Save</button>");
+    assert_eq!(
+        render(Some("Save <now>"), Some(true)),
+        r#"<button title="Save &lt;now&gt;" disabled>Save</button>"#
+    );
+    assert_eq!(render(None, Some(false)), "<button>Save</button>");
+}
+
+#[test]
+fn test_boolean_attribute_from_bool() {
+    let checked = false;
+    let open = true;
+    let out = azumi::render_to_string(&html! {
+        <details open={open} aria-expanded={open}>
+            <input type="checkbox" checked={checked} />
+        </details>
+    });
+    assert_eq!(
+        out,
+        r#"<details open aria-expanded="true"><input type="checkbox"></input></details>"#
+    );
+}
+
+#[test]
+fn test_class_list() {
+    let render = |is_active: bool, is_big: bool| {
+        azumi::render_to_string(&html! {
+            <style>
+                .item { color: "red"; }
+                .active { color: "blue"; }
+                .big { font-size: "2rem"; }
+            </style>
+            <li class={[("item", true), ("active", is_active), ("big", is_big)]}>"One"</li>
+        })
+    };
+    // Literals are written as the stylesheet's scoped names
+    let out = render(true, false);
+    let start = out.find("<li class=\"").unwrap() + "<li class=\"".len();
+    let classes: Vec<&str> = out[start..start + out[start..].find('"').unwrap()]
+        .split(' ')
+        .collect();
+    assert_eq!(classes.len(), 2);
+    assert!(classes[0].starts_with("item-s") && classes[1].starts_with("active-s"));
+    for class in &classes {
+        assert!(out.contains(&format!(".{}", class)));
+    }
+
+    let out = render(false, true);
+    assert!(out.contains(&format!("class=\"{} big-s", classes[0])));
+}
+
+#[azumi::component]
+fn Field(attrs: Attributes) -> impl azumi::Component {
+    html! { <input type="text" {..attrs} /> }
+}
+
+#[test]
+fn test_spread_attributes() {
+    let attrs = Attributes::new()
+        .set("name", "email")
+        .set("placeholder", "a\"b")
+        .flag("required", true)
+        .flag("disabled", false)
+        .set_opt("maxlength", None::<u32>);
+    let out = azumi::render_to_string(&html! { @Field(attrs = attrs.clone()) });
+    assert_eq!(
+        out,
+        r#"<input type="text" name="email" placeholder="a&quot;b" required></input>"#
+    );
+}
+
+#[test]
+fn test_attributes_map() {
+    let attrs = Attributes::new()
+        .set("type", "button")
+        .set("type", "submit")
+        .flag("hidden", true)
+        .set("bad name", "x");
+    assert_eq!(attrs.get("type"), Some("submit"));
+    assert_eq!(attrs.get("hidden"), Some(""));
+    assert_eq!(attrs.iter().count(), 3);
+}
+
+#[test]
+fn test_enumerated_attribute_keywords() {
+    // Registered rel extensions and keywords in any ASCII case compile
+    let out = azumi::render_to_string(&html! {
+        <link rel="shortcut icon" href="/favicon.ico" />
+        <a href="/partner" rel="sponsored nofollow">"Partner"</a>
+        <a href="/comment" rel="UGC">"Comment"</a>
+        <div dir="RTL">
+            <img src="/a.png" alt="A" loading="Lazy" crossorigin="Anonymous" />
+            <input type="text" inputmode="Numeric" />
+        </div>
+    });
+    assert!(out.contains(r#"rel="shortcut icon""#));
+    assert!(out.contains(r#"rel="sponsored nofollow""#));
+    assert!(out.contains(r#"<div dir="RTL">"#));
+    assert!(out.contains(r#"inputmode="Numeric""#));
+}
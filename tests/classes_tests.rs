@@ -0,0 +1,52 @@
+use azumi::html;
+
+#[derive(Clone, Copy)]
+enum Status {
+    Open,
+    Closed,
+}
+
+#[azumi::component(classes)]
+fn ticket(status: Status) -> impl azumi::Component {
+    let tone = match status {
+        Status::Open => Classes::IsOpen,
+        Status::Closed => Classes::IsClosed,
+    };
+    html! {
+        <style>
+            .ticket { padding: "1rem"; }
+            .is_open { color: "green"; }
+            .is_closed { color: "gray"; }
+            .label { font-weight: "bold"; }
+        </style>
+        <div class={ticket}>
+            <span class={tone}>"Ticket"</span>
+            @ticket_label(class_name = Classes::Label)
+        </div>
+    }
+}
+
+// Children take the parent's classes as a type, not a string
+#[azumi::component]
+fn ticket_label(class_name: ticket_component::Classes) -> impl azumi::Component {
+    html! { <b class={class_name}>"#1"</b> }
+}
+
+#[test]
+fn test_classes_enum() {
+    let open = azumi::render_to_string(&html! { @ticket(status = Status::Open) });
+    let closed = azumi::render_to_string(&html! { @ticket(status = Status::Closed) });
+
+    let class = ticket_component::Classes::IsOpen.as_str();
+    assert!(class.starts_with("is_open-"));
+    assert!(open.contains(&format!("class=\"{}\">Ticket", class)));
+    assert!(open.contains(&format!(".{}", class)));
+    assert!(closed.contains(&format!(
+        "class=\"{}\">Ticket",
+        ticket_component::Classes::IsClosed
+    )));
+    assert!(open.contains(&format!(
+        "<b class=\"{}\">#1",
+        ticket_component::Classes::Label.as_str()
+    )));
+}
@@ -0,0 +1,85 @@
+use azumi::html;
+
+#[azumi::component]
+fn tag(label: &'static str) -> impl azumi::Component {
+    html! {
+        <style>
+            @export {
+                .chip { padding: "0.25rem 0.5rem"; border-radius: "999px"; }
+                .chip_active { font-weight: "bold"; }
+            }
+            .tag { display: "inline-flex"; }
+        </style>
+        <span class={tag}><span class={chip}>{label}</span></span>
+    }
+}
+
+#[azumi::component]
+fn badge() -> impl azumi::Component {
+    html! {
+        <style>
+            .badge {
+                composes: chip chip_active from tag_component;
+                color: "red";
+            }
+        </style>
+        <span class={badge}>"New"</span>
+    }
+}
+
+#[azumi::component]
+fn pill(on: bool) -> impl azumi::Component {
+    html! {
+        <style>
+            .pill { composes: chip from tag_component; }
+            .big { font-size: "2rem"; }
+        </style>
+        <span class="pill big">"A"</span>
+        <span class={[("pill", on)]}>"B"</span>
+    }
+}
+
+fn compact(css: &str) -> String {
+    css.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[test]
+fn test_exported_classes() {
+    let chip = tag_component::exports::CHIP.as_str();
+    assert!(chip.starts_with("chip-s"));
+
+    let out = compact(&azumi::render_to_string(&html! { @tag(label = "Rust") }));
+    assert!(out.contains(&format!("class=\"{}\">Rust</span>", chip)));
+
+    // Exported rules are renamed, and written once
+    assert_eq!(out.matches(&format!(".{} {{", chip)).count(), 1);
+}
+
+#[test]
+fn test_composes_from_other_component() {
+    let out = compact(&azumi::render_to_string(&badge()));
+    let chip = tag_component::exports::CHIP;
+    let chip_active = tag_component::exports::CHIP_ACTIVE;
+
+    // The badge binding carries the composed classes, and their CSS comes along
+    assert!(out.contains(&format!(" {} {}\">New</span>", chip, chip_active)));
+    assert!(out.contains("class=\"badge-s"));
+    assert!(out.contains(&format!(".{} {{", chip)));
+    assert!(out.contains(&format!(".{} {{", chip_active)));
+    assert_eq!(out.matches(&format!(".{} {{", chip)).count(), 1);
+}
+
+#[test]
+fn test_composed_literal_classes() {
+    let out = azumi::render_to_string(&html! { @pill(on = true) });
+    let chip = tag_component::exports::CHIP;
+
+    // Literal class words carry the composed classes like the bindings do
+    let start = out.find("class=\"pill-s").unwrap() + "class=\"".len();
+    let classes: Vec<&str> = out[start..start + out[start..].find('"').unwrap()]
+        .split(' ')
+        .collect();
+    assert_eq!(classes[1], chip.as_str());
+    assert!(classes[2].starts_with("big-s"));
+    assert!(out.contains(&format!("class=\"{} {}\">B</span>", classes[0], chip)));
+}
@@ -0,0 +1,93 @@
+use azumi::html;
+
+fn label(n: i32) -> String {
+    azumi::render_to_string(&html! {
+        @if n < 0 {
+            <span>"negative"</span>
+        } else if n == 0 {
+            <span>"zero"</span>
+        } else {
+            <span>"positive"</span>
+        }
+    })
+}
+
+#[test]
+fn test_else_if_chain() {
+    assert_eq!(label(-3), "<span>negative</span>");
+    assert_eq!(label(0), "<span>zero</span>");
+    assert_eq!(label(7), "<span>positive</span>");
+}
+
+#[test]
+fn test_if_let() {
+    let render = |user: Option<&str>| {
+        azumi::render_to_string(&html! {
+            @if let Some(name) = user {
+                <p>{name}</p>
+            } else {
+                <p>"Guest"</p>
+            }
+        })
+    };
+    assert_eq!(render(Some("Ada")), "<p>Ada</p>");
+    assert_eq!(render(None), "<p>Guest</p>");
+}
+
+#[test]
+fn test_for_empty() {
+    let render = |items: Vec<&str>| {
+        azumi::render_to_string(&html! {
+            <ul>
+                @for item in &items {
+                    <li>{item}</li>
+                } @empty {
+                    <li>"No items"</li>
+                }
+            </ul>
+        })
+    };
+    assert_eq!(render(vec!["a", "b"]), "<ul><li>a</li><li>b</li></ul>");
+    assert_eq!(render(vec![]), "<ul><li>No items</li></ul>");
+}
+
+#[test]
+fn test_for_loop_context() {
+    let items = vec!["a", "b", "c"];
+    let out = azumi::render_to_string(&html! {
+        @for (item, loop) in &items {
+            @if !loop.first { ", " }
+            <span aria-posinset={loop.position} aria-setsize={loop.len}>{item}</span>
+            @if loop.last { "." }
+        }
+    });
+    assert_eq!(
+        out,
+        r#"<span aria-posinset="1" aria-setsize="3">a</span>, <span aria-posinset="2" aria-setsize="3">b</span>, <span aria-posinset="3" aria-setsize="3">c</span>."#
+    );
+}
+
+#[test]
+fn test_for_loop_context_nested_and_tuples() {
+    let rows = vec![vec![1, 2], vec![3]];
+    let out = azumi::render_to_string(&html! {
+        @for (row, loop) in &rows {
+            <p data-row={loop.index}>
+                @for (cell, loop) in row {
+                    <b data-cell={loop.index}>{cell}</b>
+                }
+            </p>
+        }
+    });
+    assert_eq!(
+        out,
+        r#"<p data-row="0"><b data-cell="0">1</b><b data-cell="1">2</b></p><p data-row="1"><b data-cell="0">3</b></p>"#
+    );
+
+    // Plain tuple patterns are not treated as loop context
+    let pairs = vec![("x", 1)];
+    let out = azumi::render_to_string(&html! {
+        @for (name, value) in &pairs { <i>{name}{value}</i> }
+    });
+    assert_eq!(out, "<i>x1</i>");
+}
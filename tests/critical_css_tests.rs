@@ -0,0 +1,61 @@
+use azumi::html;
+
+#[azumi::component]
+fn card(title: &'static str) -> impl azumi::Component {
+    html! {
+        <style>
+            .card { padding: "1rem"; }
+        </style>
+        <div class={card}>{title}</div>
+    }
+}
+
+#[test]
+fn test_critical_css_is_collected_once() {
+    let page = |n: usize| {
+        let titles: Vec<&'static str> = vec!["Card"; n];
+        html! {
+            <html>
+                <head>{azumi::styles::critical_css()}<title>"Cards"</title></head>
+                <body>
+                    @for title in &titles {
+                        @card(title = title)
+                    }
+                </body>
+            </html>
+        }
+    };
+
+    let out = azumi::styles::render_with_critical_css(&page(3));
+    assert_eq!(out.matches("<style").count(), 1);
+    assert!(out.starts_with("<html><head><style data-azumi-critical>"));
+    assert!(out.contains("</style><title>Cards</title></head>"));
+    assert_eq!(out.matches("<div").count(), 3);
+
+    // Nothing rendered, nothing collected
+    let empty = azumi::styles::render_with_critical_css(&page(0));
+    assert_eq!(empty.matches("<style").count(), 0);
+
+    // Without the marker the CSS goes before </head>
+    let plain = azumi::styles::render_with_critical_css(&html! {
+        <html><head><title>"x"</title></head><body>@card(title = "a")</body></html>
+    });
+    assert!(plain.contains("<title>x</title><style data-azumi-critical>"));
+
+    // Ordinary renders still inline
+    assert_eq!(
+        azumi::render_to_string(&page(2)).matches("<style").count(),
+        2
+    );
+}
+
+#[test]
+fn test_collecting_stops_after_panic() {
+    let broken = azumi::from_fn(|_| panic!("render failed"));
+    let result = std::panic::catch_unwind(|| azumi::styles::render_with_critical_css(&broken));
+    assert!(result.is_err());
+
+    // Later renders on this thread inline their CSS again
+    let out = azumi::render_to_string(&html! { @card(title = "a") });
+    assert!(out.contains("<style"));
+}
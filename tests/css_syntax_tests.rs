@@ -0,0 +1,65 @@
+use azumi::html;
+
+#[azumi::component]
+fn panel() -> impl azumi::Component {
+    html! {
+        <style>
+            @custom-media --narrow (max-width: "600px");
+            @layer base {
+                .panel { margin: "0"; }
+            }
+            .panel {
+                padding: "1rem";
+                &:hover { color: "oklch(70% 0.1 200)"; }
+                .title { font-weight: "bold"; }
+            }
+            @media (--narrow) {
+                .panel { padding: "0.5rem"; }
+            }
+        </style>
+        <div class={panel}><h2 class={title}>"Panel"</h2></div>
+    }
+}
+
+#[test]
+fn test_modern_css_syntax() {
+    let out = azumi::render_to_string(&panel());
+
+    // Classes inside nested rules get bindings like top-level ones
+    assert!(out.contains("class=\"title-"));
+
+    // Quoted values in at-rule preludes become plain CSS, custom media is resolved
+    assert!(out.contains("@media (width <= 600px)"));
+    assert!(!out.contains("--narrow"));
+    assert!(out.contains("@layer base"));
+
+    // `.title` nested inside `.panel` is a descendant, `&:hover` is not
+    assert!(out.contains("&:hover"));
+    assert!(out.contains("& .title-"));
+}
+
+#[azumi::component]
+fn note() -> impl azumi::Component {
+    html! {
+        <style>
+            .note { color: "red"; }
+            .note em { font-style: "normal"; }
+            .wide { width: "100%"; }
+        </style>
+        <p class="note wide">"A "<em>"note"</em></p>
+    }
+}
+
+#[test]
+fn test_static_classes_are_scoped() {
+    let out = azumi::render_to_string(&note());
+
+    // Static class words get the same suffix as the stylesheet's classes
+    let start = out.find("class=\"note-").unwrap() + "class=\"".len();
+    let classes = &out[start..start + out[start..].find('"').unwrap()];
+    let (note, wide) = classes.split_once(' ').unwrap();
+    assert!(wide.starts_with("wide-"));
+    assert!(out.contains(&format!(".{} {{", note)));
+    assert!(out.contains(&format!(".{} em {{", note)));
+    assert!(out.contains(&format!(".{} {{", wide)));
+}
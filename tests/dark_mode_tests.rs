@@ -0,0 +1,52 @@
+use azumi::html;
+
+#[azumi::component]
+fn card() -> impl azumi::Component {
+    html! {
+        <style>
+            .card {
+                background: "white";
+                @dark { background: "#111827"; }
+            }
+            .title, .subtitle:is(.big, .small) { color: "black"; }
+            @used(subtitle, big, small);
+            @dark {
+                .title { color: "white"; }
+                @media (max-width: "600px") {
+                    .card { border: "none"; }
+                }
+            }
+        </style>
+        <div class={card}><h2 class={title}>"Card"</h2></div>
+    }
+}
+
+fn compact(css: &str) -> String {
+    css.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[test]
+fn test_dark_blocks() {
+    let out = compact(&azumi::render_to_string(&card()));
+    assert!(!out.contains("@dark"));
+
+    // Top-level @dark prefixes each rule; renaming stays on the rule's own classes
+    assert!(out.contains(
+        "@media (prefers-color-scheme: dark) { :root:not([data-theme=\"light\"]) .title-"
+    ));
+    assert!(out.contains("[data-theme=\"dark\"] .title-"));
+    assert!(out.contains("@media (width <= 600px) { [data-theme=\"dark\"] .card-"));
+
+    // ...which are the names the elements render with
+    let start = out.find("<h2 class=\"").unwrap() + "<h2 class=\"".len();
+    let title = &out[start..start + out[start..].find('"').unwrap()];
+    assert!(title.starts_with("title-s"));
+    assert!(out.contains(&format!(
+        "[data-theme=\"dark\"] .{} {{ color: #fff; }}",
+        title
+    )));
+
+    // @dark inside a rule nests under the parent with `&`
+    assert!(out.contains(":root:not([data-theme=\"light\"]) & { background: #111827; }"));
+    assert!(out.contains("[data-theme=\"dark\"] & { background: #111827; }"));
+}
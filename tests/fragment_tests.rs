@@ -0,0 +1,72 @@
+use azumi::html;
+
+#[azumi::component]
+fn todo_list(todos: Vec<String>) -> impl azumi::Component {
+    html! {
+        <section>
+            <h2>"Todos"</h2>
+            <ul>
+                @for todo in &todos {
+                    <fragment name="row"><li>{todo}</li></fragment>
+                }
+            </ul>
+            <fragment name="count"><p>{todos.len()}" items"</p></fragment>
+        </section>
+    }
+}
+
+fn page(todos: &[&str]) -> impl azumi::Component {
+    todo_list_component::render(
+        todo_list_component::Props::builder()
+            .todos(todos.iter().map(|t| t.to_string()).collect())
+            .build()
+            .unwrap(),
+    )
+}
+
+#[test]
+fn test_full_render_has_no_markers() {
+    let out = azumi::render_to_string(&page(&["a", "b"]));
+    assert_eq!(
+        out,
+        "<section><h2>Todos</h2><ul><li>a</li><li>b</li></ul><p>2 items</p></section>"
+    );
+}
+
+#[test]
+fn test_render_fragment_by_name() {
+    let component = page(&["a", "b"]);
+    assert_eq!(
+        azumi::render_fragment(&component, "row").as_deref(),
+        Some("<li>a</li><li>b</li>")
+    );
+    assert_eq!(
+        azumi::render_fragment(&component, "count").as_deref(),
+        Some("<p>2 items</p>")
+    );
+    assert_eq!(azumi::render_fragment(&component, "missing"), None);
+
+    // Target is reset afterwards
+    assert!(!azumi::render_to_string(&component).contains('\u{E000}'));
+}
+
+#[tokio::test]
+async fn test_action_response_fragment() {
+    use axum::response::IntoResponse;
+    let res = azumi::action::ActionResponse::fragment(&page(&["x"]), "count").into_response();
+    assert_eq!(res.status(), axum::http::StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "<p>1 items</p>");
+}
+
+#[test]
+fn test_target_reset_after_panic() {
+    let broken = azumi::from_fn(|_| panic!("render failed"));
+    let result = std::panic::catch_unwind(|| azumi::render_fragment(&broken, "row"));
+    assert!(result.is_err());
+
+    // The panicking render doesn't leave the thread rendering fragment markers
+    assert!(!azumi::render_to_string(&page(&["a"])).contains('\u{E000}'));
+}
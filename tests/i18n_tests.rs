@@ -0,0 +1,132 @@
+use axum::{body::Body, http::Request, routing::get, Router};
+use azumi::i18n::{self, Locale};
+use azumi::{html, t};
+use tower::ServiceExt;
+
+fn greeting(name: &str) -> String {
+    azumi::render_to_string(&html! {
+        <p>{t!("greeting", name = name)}" - "{t!("nav.home")}</p>
+    })
+}
+
+#[test]
+fn test_translations_follow_locale() {
+    assert_eq!(
+        i18n::with_locale("fr", || greeting("Ada")),
+        "<p>Bonjour, Ada ! - Accueil</p>"
+    );
+    assert_eq!(
+        i18n::with_locale("en-GB", || greeting("Ada")),
+        "<p>Hello, Ada! - Home</p>"
+    );
+    // Unsupported locale and no locale fall back to English
+    assert_eq!(
+        i18n::with_locale("de", || greeting("Ada")),
+        "<p>Hello, Ada! - Home</p>"
+    );
+    assert_eq!(greeting("Ada"), "<p>Hello, Ada! - Home</p>");
+}
+
+#[test]
+fn test_locale_reset_after_panic() {
+    let result = std::panic::catch_unwind(|| i18n::with_locale("fr", || panic!("render failed")));
+    assert!(result.is_err());
+
+    // The panicking render doesn't leave the thread in French
+    assert!(i18n::current().is_none());
+    assert_eq!(greeting("Ada"), "<p>Hello, Ada! - Home</p>");
+}
+
+#[test]
+fn test_arguments_are_escaped() {
+    assert_eq!(greeting("<b>"), "<p>Hello, &lt;b&gt;! - Home</p>");
+}
+
+#[test]
+fn test_negotiate() {
+    let supported = ["en", "fr", "ar"];
+    assert_eq!(
+        i18n::negotiate("fr-CA,fr;q=0.9,en;q=0.8", &supported).tag(),
+        "fr"
+    );
+    assert_eq!(
+        i18n::negotiate("de, ar;q=0.5, en;q=0.7", &supported).tag(),
+        "en"
+    );
+    assert_eq!(i18n::negotiate("de", &supported).tag(), "en");
+    assert_eq!(i18n::negotiate("", &supported).tag(), "en");
+    assert_eq!(i18n::negotiate("fr;q=0", &supported).tag(), "en");
+}
+
+#[test]
+fn test_locale_direction() {
+    assert_eq!(Locale::new("ar-EG").dir(), "rtl");
+    assert_eq!(Locale::new("he").dir(), "rtl");
+    assert_eq!(Locale::new("fr-CA").dir(), "ltr");
+    assert_eq!(Locale::new("fr-CA").language(), "fr");
+}
+
+#[test]
+fn test_html_element_gets_lang_and_dir() {
+    let page = || {
+        azumi::render_to_string(&html! {
+            <html><body><p>{t!("items", count = 3)}</p></body></html>
+        })
+    };
+    assert_eq!(
+        i18n::with_locale("ar", page),
+        "<html lang=\"ar\" dir=\"rtl\"><body><p>3 عناصر</p></body></html>"
+    );
+    // Without a locale nothing is added
+    assert_eq!(page(), "<html><body><p>3 items</p></body></html>");
+
+    // An explicit lang decides dir, whatever the negotiated locale
+    let explicit = i18n::with_locale("ar", || {
+        azumi::render_to_string(&html! { <html lang="en"><body></body></html> })
+    });
+    assert_eq!(
+        explicit,
+        "<html lang=\"en\" dir=\"ltr\"><body></body></html>"
+    );
+    let hebrew = i18n::with_locale("en", || {
+        azumi::render_to_string(&html! { <html lang="he"><body></body></html> })
+    });
+    assert_eq!(hebrew, "<html lang=\"he\" dir=\"rtl\"><body></body></html>");
+
+    // Explicit dir is kept as written; lang still comes from the locale
+    let ltr = i18n::with_locale("ar", || {
+        azumi::render_to_string(&html! { <html dir="ltr"><body></body></html> })
+    });
+    assert_eq!(ltr, "<html dir=\"ltr\" lang=\"ar\"><body></body></html>");
+
+    // A dynamic lang gets no dir added
+    let lang = "ar";
+    let dynamic = i18n::with_locale("en", || {
+        azumi::render_to_string(&html! { <html lang={lang}><body></body></html> })
+    });
+    assert_eq!(dynamic, "<html lang=\"ar\"><body></body></html>");
+}
+
+#[tokio::test]
+async fn test_localize_middleware() {
+    async fn page(locale: Locale) -> String {
+        format!("{}:{}", locale, greeting("Ada"))
+    }
+    let app = i18n::localize(Router::new().route("/", get(page)), &["en", "fr"]);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/")
+                .header("accept-language", "fr-FR,fr;q=0.9")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["vary"], "accept-language");
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "fr:<p>Bonjour, Ada ! - Accueil</p>");
+}
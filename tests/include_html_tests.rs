@@ -0,0 +1,24 @@
+use azumi::html;
+
+#[test]
+fn test_include_html_matches_inline_template() {
+    let title = "Report";
+    let items = ["a", "b"];
+    let included = azumi::render_to_string(&azumi::include_html!("tests/fixtures/page.azumi"));
+
+    let inline = azumi::render_to_string(&html! {
+        <style>
+            .page { padding: "1rem"; }
+        </style>
+        <main class={page}>
+            <h1>{title}</h1>
+            @for item in &items {
+                <p>{item}</p>
+            }
+        </main>
+    });
+
+    assert_eq!(included, inline);
+    assert!(included.contains(">Report</h1>"));
+    assert!(included.contains(">a</p>"));
+}
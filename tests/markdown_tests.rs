@@ -0,0 +1,59 @@
+use azumi::html;
+
+#[azumi::component]
+fn badge(text: &'static str) -> impl azumi::Component {
+    html! { <span>{text}</span> }
+}
+
+#[test]
+fn test_markdown_block() {
+    let out = azumi::render_to_string(&html! {
+        <article>
+            @markdown {
+                r#"
+                # Lesson 1
+
+                Text with *emphasis*, `Vec<T>` and a [link](/docs?a=1&b=2).
+
+                ```rust
+                let x = 1 < 2;
+                ```
+                "#
+            }
+        </article>
+    });
+    assert_eq!(
+        out,
+        "<article><h1>Lesson 1</h1>\
+         <p>Text with <em>emphasis</em>, <code>Vec&lt;T&gt;</code> and a <a href=\"/docs?a=1&amp;b=2\">link</a>.</p>\
+         <pre><code data-lang=\"rust\">let x = 1 &lt; 2;\n</code></pre></article>"
+    );
+}
+
+#[test]
+fn test_markdown_embeds_components() {
+    let count = 3;
+    let out = azumi::render_to_string(&html! {
+        @markdown {
+            "## Status"
+            @badge(text = "new")
+            <p>{count}" items"</p>
+            "Done."
+        }
+    });
+    assert_eq!(
+        out,
+        "<h2>Status</h2><span>new</span><p>3 items</p><p>Done.</p>"
+    );
+}
+
+#[test]
+fn test_markdown_file() {
+    let out = azumi::render_to_string(&azumi::markdown!("tests/fixtures/intro.md"));
+    assert_eq!(
+        out,
+        "<h1>Getting Started</h1>\
+         <p>Azumi checks your <strong>templates</strong> at compile time.</p>\
+         <ol><li>Write a component</li><li>Run <code>cargo build</code></li></ol>"
+    );
+}
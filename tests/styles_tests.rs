@@ -0,0 +1,75 @@
+use axum::{body::Body, http::Request, Router};
+use azumi::html;
+use tower::ServiceExt;
+
+#[azumi::component]
+fn card(title: &'static str) -> impl azumi::Component {
+    html! {
+        <style>
+            .card { padding: "1rem"; }
+        </style>
+        <div class={card}>{title}</div>
+    }
+}
+
+fn cards(n: usize) -> String {
+    let titles: Vec<&'static str> = vec!["Card"; n];
+    azumi::render_to_string(&html! {
+        <section>
+            @for title in &titles {
+                @card(title = title)
+            }
+        </section>
+    })
+}
+
+async fn get(app: Router, uri: &str, etag: Option<&str>) -> axum::response::Response {
+    let mut req = Request::builder().uri(uri);
+    if let Some(etag) = etag {
+        req = req.header("if-none-match", etag);
+    }
+    app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap()
+}
+
+// One test: serving the bundle flips a process-wide switch
+#[tokio::test]
+async fn test_css_bundle() {
+    // Inline by default: every card repeats its CSS
+    assert_eq!(cards(3).matches("<style").count(), 3);
+
+    // The bundle holds each stylesheet once, in a stable order
+    let bundle = azumi::styles::bundle();
+    assert_eq!(bundle.css.matches("padding: 1rem").count(), 1);
+    assert_eq!(bundle.hash.len(), 16);
+
+    let app = azumi::styles::serve_bundle(Router::new());
+    assert!(azumi::styles::is_bundled());
+    assert!(!cards(3).contains("<style"));
+
+    let link = azumi::render_to_string(&azumi::styles::stylesheet_link());
+    assert_eq!(
+        link,
+        format!(
+            "<link rel=\"stylesheet\" href=\"/azumi.css?v={}\">",
+            bundle.hash
+        )
+    );
+
+    let res = get(app.clone(), &format!("/azumi.css?v={}", bundle.hash), None).await;
+    assert_eq!(res.headers()["content-type"], "text/css; charset=utf-8");
+    assert_eq!(
+        res.headers()["cache-control"],
+        "public, max-age=31536000, immutable"
+    );
+    let etag = res.headers()["etag"].to_str().unwrap().to_string();
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, bundle.css);
+
+    let res = get(app.clone(), "/azumi.css", None).await;
+    assert_eq!(res.headers()["cache-control"], "no-cache");
+
+    let res = get(app, "/azumi.css", Some(&etag)).await;
+    assert_eq!(res.status(), axum::http::StatusCode::NOT_MODIFIED);
+}
@@ -0,0 +1,53 @@
+use azumi::html;
+
+#[test]
+fn test_inline_svg_icon() {
+    let icon_id = "icon_check";
+    let out = azumi::render_to_string(&html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" aria-hidden="true">
+            <defs>
+                <linearGradient id={icon_id} gradientUnits="userSpaceOnUse">
+                    <stop offset="0" stop-color="red" />
+                </linearGradient>
+            </defs>
+            <path d="M5 13l4 4L19 7" stroke-width="2" fill="none" />
+            <use xlink:href="#check" />
+        </svg>
+    });
+    assert!(out.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24""#));
+    assert!(out.contains(r#"<linearGradient id="icon_check" gradientUnits="userSpaceOnUse">"#));
+    assert!(out.contains(r##"<use xlink:href="#check"></use>"##));
+}
+
+#[test]
+fn test_inline_mathml() {
+    let out = azumi::render_to_string(&html! {
+        <math display="block">
+            <mfrac><mi>"a"</mi><mn>"2"</mn></mfrac>
+        </math>
+    });
+    assert_eq!(
+        out,
+        r#"<math display="block"><mfrac><mi>a</mi><mn>2</mn></mfrac></math>"#
+    );
+}
+
+#[test]
+fn test_svg_document_and_lighting_attributes() {
+    let filter_id = "light";
+    let out = azumi::render_to_string(&html! {
+        <svg xmlns="http://www.w3.org/2000/svg" version="1.1" baseProfile="full" viewBox="0 0 10 10">
+            <filter id={filter_id}>
+                <feDiffuseLighting in="SourceGraphic" surfaceScale="2" kernelUnitLength="1">
+                    <fePointLight x="5" y="5" z="10" />
+                </feDiffuseLighting>
+                <feSpecularLighting in="SourceGraphic" specularExponent="20">
+                    <feSpotLight x="0" y="0" z="8" pointsAtX="5" pointsAtY="5" pointsAtZ="0" />
+                </feSpecularLighting>
+            </filter>
+        </svg>
+    });
+    assert!(out.contains(r#"version="1.1" baseProfile="full""#));
+    assert!(out.contains(r#"<fePointLight x="5" y="5" z="10">"#));
+    assert!(out.contains(r#"<feSpotLight x="0" y="0" z="8""#));
+}
@@ -0,0 +1,81 @@
+use azumi::html;
+
+#[azumi::component]
+fn badge(gap: &'static str) -> impl azumi::Component {
+    html! {
+        <style>
+            .badge {
+                --badge-size: "2rem";
+                color: "var(--color-primary)";
+                padding: "var(--space-md)";
+                height: "var(--badge-size)";
+                gap: "var(--badge-gap)";
+            }
+        </style>
+        <span class={badge} style={ --badge-gap: gap; --color-primary: "#0f766e" }>"New"</span>
+    }
+}
+
+// --card-accent comes from whichever card the badge sits in
+#[azumi::component]
+fn card_badge() -> impl azumi::Component {
+    html! {
+        <style>
+            @inherited(--card-accent);
+            .card_badge {
+                color: "var(--card-accent)";
+                margin: "var(--card-gap, 0.5rem)";
+            }
+        </style>
+        <span class={card_badge}>"Hot"</span>
+    }
+}
+
+// Sets --card-accent inline for the card_badge inside it
+#[azumi::component]
+fn accent_card() -> impl azumi::Component {
+    html! {
+        <style>
+            @provides(--card-accent);
+            .accent_card { padding: "var(--space-md)"; }
+        </style>
+        <div class={accent_card} style={ --card-accent: "red" }>@card_badge()</div>
+    }
+}
+
+#[test]
+fn test_theme_root_css() {
+    let out = azumi::render_to_string(&azumi::theme!());
+    assert!(out.starts_with("<style>:root {"));
+    assert!(out.contains("--color-primary: #4f46e5;"));
+    assert!(out.contains("--font-body: system-ui, sans-serif;"));
+
+    // Dark variants follow the OS unless data-theme picks one
+    assert!(out.contains("@media (prefers-color-scheme: dark)"));
+    assert!(out.contains(":root:not([data-theme=\"light\"])"));
+    assert!(out.contains("[data-theme=\"dark\"] {\n  --color-surface: #111827;"));
+}
+
+#[test]
+fn test_tokens_and_local_variables() {
+    let out = azumi::render_to_string(&html! { @badge(gap = "4px") });
+    assert!(out.contains("color: var(--color-primary)"));
+    assert!(out.contains("style=\"--badge-gap: 4px; --color-primary: #0f766e\""));
+}
+
+#[test]
+fn test_inherited_and_fallback_variables() {
+    let out = azumi::render_to_string(&card_badge());
+    assert!(!out.contains("@inherited"));
+    assert!(out.contains("color: var(--card-accent)"));
+    assert!(out.contains("margin: var(--card-gap, .5rem)"));
+}
+
+#[test]
+fn test_provided_variables() {
+    let out = azumi::render_to_string(&accent_card());
+    assert!(!out.contains("@provides"));
+    assert!(out.contains("style=\"--card-accent: red\">"));
+    assert!(out.contains("color: var(--card-accent)"));
+    assert!(out.contains("<span class=\"card_badge-"));
+}